@@ -0,0 +1,406 @@
+//! Cookie-related functionality for WebDriver.
+
+use crate::{error, Client};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value as Json;
+use webdriver::command::{AddCookieParameters, WebDriverCommand};
+use webdriver::common::Date;
+use webdriver::error::{ErrorStatus, WebDriverError};
+
+/// The [`SameSite`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Set-Cookie/SameSite)
+/// attribute of a [`Cookie`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum SameSite {
+    /// The cookie is only sent with requests from the site that set it.
+    Strict,
+    /// The cookie is also sent when navigating to the site that set it from elsewhere.
+    Lax,
+    /// The cookie is sent with all requests, including cross-site ones.
+    None,
+}
+
+/// A cookie, as WebDriver [represents it](https://www.w3.org/TR/webdriver1/#cookies).
+///
+/// Cookies are returned by [`Client::get_all_cookies`] and [`Client::get_named_cookie`], and
+/// can be added with [`Client::add_cookie`]:
+///
+/// ```rust,no_run
+/// # use fantoccini::{ClientBuilder, cookies::Cookie};
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), fantoccini::error::CmdError> {
+/// # let mut c = ClientBuilder::native().connect("http://localhost:4444").await.expect("failed to connect to WebDriver");
+/// c.goto("https://www.wikipedia.org/").await?;
+/// let mut cookie = Cookie::new("flavor", "oatmeal");
+/// cookie.set_path("/").set_http_only(true);
+/// c.add_cookie(cookie).await?;
+/// # c.close().await
+/// # }
+/// ```
+///
+/// It converts to and from the [`cookie`](https://docs.rs/cookie) crate's `Cookie` type.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    name: String,
+    value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    domain: Option<String>,
+    #[serde(default)]
+    secure: bool,
+    #[serde(default)]
+    http_only: bool,
+    // the legacy protocol reports the expiry as a float
+    #[serde(
+        default,
+        deserialize_with = "deserialize_expiry",
+        skip_serializing_if = "Option::is_none"
+    )]
+    expiry: Option<u64>,
+    // drivers may report values other than the three the spec knows about
+    #[serde(
+        default,
+        deserialize_with = "deserialize_same_site",
+        skip_serializing_if = "Option::is_none"
+    )]
+    same_site: Option<SameSite>,
+}
+
+fn deserialize_expiry<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let expiry = Option::<f64>::deserialize(deserializer)?;
+    Ok(expiry.map(|secs| secs.max(0.0) as u64))
+}
+
+fn deserialize_same_site<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<SameSite>, D::Error> {
+    let same_site = Option::<String>::deserialize(deserializer)?;
+    Ok(match same_site.as_deref() {
+        Some("Strict") => Some(SameSite::Strict),
+        Some("Lax") => Some(SameSite::Lax),
+        Some("None") => Some(SameSite::None),
+        _ => None,
+    })
+}
+
+impl Cookie {
+    /// Create a cookie with the given name and value, and no other attributes.
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            domain: None,
+            secure: false,
+            http_only: false,
+            expiry: None,
+            same_site: None,
+        }
+    }
+
+    /// The name of the cookie.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the cookie.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The path the cookie is for, if any.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// The domain the cookie is for, if any.
+    pub fn domain(&self) -> Option<&str> {
+        self.domain.as_deref()
+    }
+
+    /// Whether the cookie is only sent over secure connections.
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    /// Whether the cookie is hidden from scripts on the page.
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// When the cookie expires, in seconds since the Unix epoch.
+    ///
+    /// `None` means the cookie lasts until the end of the browsing session.
+    pub fn expiry(&self) -> Option<u64> {
+        self.expiry
+    }
+
+    /// The `SameSite` attribute of the cookie, if any.
+    pub fn same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+
+    /// Set the value of the cookie.
+    pub fn set_value(&mut self, value: impl Into<String>) -> &mut Self {
+        self.value = value.into();
+        self
+    }
+
+    /// Set the path the cookie is for.
+    pub fn set_path(&mut self, path: impl Into<String>) -> &mut Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Set the domain the cookie is for.
+    pub fn set_domain(&mut self, domain: impl Into<String>) -> &mut Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Set whether the cookie is only sent over secure connections.
+    pub fn set_secure(&mut self, secure: bool) -> &mut Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Set whether the cookie is hidden from scripts on the page.
+    pub fn set_http_only(&mut self, http_only: bool) -> &mut Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set when the cookie expires, in seconds since the Unix epoch.
+    pub fn set_expiry(&mut self, expiry: Option<u64>) -> &mut Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Set the `SameSite` attribute of the cookie.
+    pub fn set_same_site(&mut self, same_site: Option<SameSite>) -> &mut Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Parse a single cookie as returned by the WebDriver server.
+    pub(crate) fn from_json(json: Json) -> Result<Self, error::CmdError> {
+        match serde_json::from_value(json.clone()) {
+            Ok(cookie) => Ok(cookie),
+            Err(_) => Err(error::CmdError::NotW3C(json)),
+        }
+    }
+}
+
+impl From<cookie::Cookie<'_>> for Cookie {
+    /// Convert a cookie from the `cookie` crate.
+    ///
+    /// A `Max-Age` is turned into an expiry time relative to now.
+    fn from(c: cookie::Cookie<'_>) -> Self {
+        let expiry = if let Some(expires) = c.expires() {
+            Some(expires.unix_timestamp().max(0) as u64)
+        } else if let Some(max_age) = c.max_age() {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            Some((now + max_age.whole_seconds()).max(0) as u64)
+        } else {
+            None
+        };
+        Cookie {
+            name: c.name().to_string(),
+            value: c.value().to_string(),
+            path: c.path().map(String::from),
+            domain: c.domain().map(String::from),
+            secure: c.secure().unwrap_or(false),
+            http_only: c.http_only().unwrap_or(false),
+            expiry,
+            same_site: c.same_site().map(|same_site| match same_site {
+                cookie::SameSite::Strict => SameSite::Strict,
+                cookie::SameSite::Lax => SameSite::Lax,
+                cookie::SameSite::None => SameSite::None,
+            }),
+        }
+    }
+}
+
+impl From<Cookie> for cookie::Cookie<'static> {
+    fn from(c: Cookie) -> Self {
+        let mut cookie = cookie::Cookie::new(c.name, c.value);
+        if let Some(path) = c.path {
+            cookie.set_path(path);
+        }
+        if let Some(domain) = c.domain {
+            cookie.set_domain(domain);
+        }
+        cookie.set_secure(c.secure);
+        cookie.set_http_only(c.http_only);
+        if let Some(expiry) = c.expiry {
+            cookie.set_expires(time::OffsetDateTime::from_unix_timestamp(expiry as i64));
+        }
+        if let Some(same_site) = c.same_site {
+            cookie.set_same_site(match same_site {
+                SameSite::Strict => cookie::SameSite::Strict,
+                SameSite::Lax => cookie::SameSite::Lax,
+                SameSite::None => cookie::SameSite::None,
+            });
+        }
+        cookie
+    }
+}
+
+impl From<Cookie> for AddCookieParameters {
+    fn from(c: Cookie) -> Self {
+        AddCookieParameters {
+            name: c.name,
+            value: c.value,
+            path: c.path,
+            domain: c.domain,
+            secure: c.secure,
+            httpOnly: c.http_only,
+            expiry: c.expiry.map(Date),
+            sameSite: c.same_site.map(|same_site| {
+                match same_site {
+                    SameSite::Strict => "Strict",
+                    SameSite::Lax => "Lax",
+                    SameSite::None => "None",
+                }
+                .to_string()
+            }),
+        }
+    }
+}
+
+/// [Cookies](https://www.w3.org/TR/webdriver1/#cookies)
+impl Client {
+    /// Get all cookies associated with the current document.
+    ///
+    /// See [14.1 Get All Cookies](https://www.w3.org/TR/webdriver1/#get-all-cookies) of the
+    /// WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Get All Cookies"))]
+    pub async fn get_all_cookies(&mut self) -> Result<Vec<Cookie>, error::CmdError> {
+        match self.issue(WebDriverCommand::GetCookies).await? {
+            Json::Array(cookies) => cookies.into_iter().map(Cookie::from_json).collect(),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Get a single named cookie associated with the current document.
+    ///
+    /// If there is no such cookie, [`CmdError::NoSuchCookie`](error::CmdError::NoSuchCookie) is
+    /// returned.
+    ///
+    /// See [14.2 Get Named Cookie](https://www.w3.org/TR/webdriver1/#get-named-cookie) of the
+    /// WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Get Named Cookie"))]
+    pub async fn get_named_cookie(&mut self, name: &str) -> Result<Cookie, error::CmdError> {
+        if self.is_legacy() {
+            // the legacy protocol has no command for getting a single cookie
+            return self
+                .get_all_cookies()
+                .await?
+                .into_iter()
+                .find(|cookie| cookie.name() == name)
+                .ok_or_else(|| {
+                    error::CmdError::NoSuchCookie(WebDriverError::new(
+                        ErrorStatus::NoSuchCookie,
+                        format!("no cookie named {}", name),
+                    ))
+                });
+        }
+
+        let res = self
+            .issue(WebDriverCommand::GetNamedCookie(name.to_string()))
+            .await?;
+        Cookie::from_json(res)
+    }
+
+    /// Add the given cookie to the current document.
+    ///
+    /// The cookie must be for the domain of the current document (which is what it defaults to
+    /// if it has no domain), so navigate there first. See
+    /// [`Client::set_cookie_cdp`] for setting cookies for any domain in Chromium-based browsers.
+    ///
+    /// Cookies from the [`cookie`](https://docs.rs/cookie) crate can be passed here too.
+    ///
+    /// See [14.3 Add Cookie](https://www.w3.org/TR/webdriver1/#add-cookie) of the WebDriver
+    /// standard.
+    #[cfg_attr(docsrs, doc(alias = "Add Cookie"))]
+    pub async fn add_cookie(&mut self, cookie: impl Into<Cookie>) -> Result<(), error::CmdError> {
+        let params = AddCookieParameters::from(cookie.into());
+        self.issue(WebDriverCommand::AddCookie(params)).await?;
+        Ok(())
+    }
+
+    /// Delete the cookie with the given name from the current document.
+    ///
+    /// This is a no-op if there is no such cookie.
+    ///
+    /// See [14.4 Delete Cookie](https://www.w3.org/TR/webdriver1/#delete-cookie) of the WebDriver
+    /// standard.
+    #[cfg_attr(docsrs, doc(alias = "Delete Cookie"))]
+    pub async fn delete_cookie(&mut self, name: &str) -> Result<(), error::CmdError> {
+        self.issue(WebDriverCommand::DeleteCookie(name.to_string()))
+            .await?;
+        Ok(())
+    }
+
+    /// Delete all cookies associated with the current document.
+    ///
+    /// Only the cookies the current document can see are deleted. See
+    /// [`Client::clear_cookies_cdp`] for deleting the cookies of all domains in Chromium-based
+    /// browsers.
+    ///
+    /// See [14.5 Delete All Cookies](https://www.w3.org/TR/webdriver1/#delete-all-cookies) of the
+    /// WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Delete All Cookies"))]
+    pub async fn delete_all_cookies(&mut self) -> Result<(), error::CmdError> {
+        self.issue(WebDriverCommand::DeleteCookies).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn w3c_serialization() {
+        let mut cookie = Cookie::new("flavor", "oatmeal");
+        cookie
+            .set_path("/")
+            .set_http_only(true)
+            .set_expiry(Some(1_700_000_000))
+            .set_same_site(Some(SameSite::Lax));
+        let json = serde_json::to_value(&cookie).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "flavor",
+                "value": "oatmeal",
+                "path": "/",
+                "secure": false,
+                "httpOnly": true,
+                "expiry": 1_700_000_000u64,
+                "sameSite": "Lax",
+            })
+        );
+        assert_eq!(Cookie::from_json(json).unwrap(), cookie);
+    }
+
+    #[test]
+    fn lenient_deserialization() {
+        // as reported by legacy drivers, and drivers that know other SameSite values
+        let json = serde_json::json!({
+            "name": "flavor",
+            "value": "oatmeal",
+            "expiry": 1_700_000_000.5,
+            "sameSite": "no_restriction",
+        });
+        let cookie = Cookie::from_json(json).unwrap();
+        assert_eq!(cookie.expiry(), Some(1_700_000_000));
+        assert_eq!(cookie.same_site(), None);
+        assert!(Cookie::from_json(serde_json::json!({ "name": "flavor" })).is_err());
+    }
+}
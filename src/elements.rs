@@ -1,9 +1,19 @@
 //! Types used to represent particular elements on a page.
 
-use crate::{error, Client, Locator};
-use serde::Serialize;
+use crate::keys::{Key, KeySequence};
+use crate::{error, Client, Condition, Locator};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::Value as Json;
-use webdriver::command::{SendKeysParameters, SwitchToFrameParameters, WebDriverCommand};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use webdriver::actions::{
+    ActionSequence, ActionsType, GeneralAction, PauseAction, PointerAction, PointerActionItem,
+    PointerActionParameters, PointerDownAction, PointerMoveAction, PointerOrigin, PointerType,
+    PointerUpAction,
+};
+use webdriver::command::{
+    ActionsParameters, SendKeysParameters, SwitchToFrameParameters, WebDriverCommand,
+};
 use webdriver::common::FrameId;
 use webdriver::error::WebDriverError;
 
@@ -27,6 +37,167 @@ pub struct Form {
     pub(crate) form: webdriver::common::WebElement,
 }
 
+/// What happened after submitting a form with [`Form::submit_and_wait`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SubmitOutcome {
+    /// The browser left the page the form was on, and finished loading the given URL.
+    Navigated(url::Url),
+    /// The browser stayed on the page, and the condition at this index of those passed to
+    /// [`Form::submit_and_wait`] was met.
+    Condition(usize),
+}
+
+/// A self-contained snapshot of an element, as taken by [`Element::debug_snapshot`].
+///
+/// This serializes to JSON (with the screenshot base64-encoded), so that it can be saved when a
+/// test fails and inspected later.
+#[derive(Clone, Debug, Serialize)]
+pub struct ElementSnapshot {
+    /// The HTML of the element, including the element itself.
+    pub outer_html: String,
+    /// Every computed style property of the element, by name.
+    pub styles: BTreeMap<String, String>,
+    /// The `(x, y, width, height)` of the element's bounding box, relative to the viewport, in
+    /// CSS pixels.
+    pub rect: (f64, f64, f64, f64),
+    /// A PNG-encoded screenshot of the element.
+    #[serde(serialize_with = "serialize_base64")]
+    pub screenshot: Vec<u8>,
+}
+
+/// The part of an `ElementSnapshot` that is gathered by `SNAPSHOT_JS`.
+#[derive(Deserialize)]
+struct PageSnapshot {
+    html: String,
+    styles: BTreeMap<String, String>,
+    rect: (f64, f64, f64, f64),
+}
+
+/// Returns the outer HTML, computed styles, and bounding box of the element `arguments[0]`.
+const SNAPSHOT_JS: &str = r#"
+const e = arguments[0];
+const computed = window.getComputedStyle(e);
+const styles = {};
+for (let i = 0; i < computed.length; i++) {
+    styles[computed[i]] = computed.getPropertyValue(computed[i]);
+}
+const rect = e.getBoundingClientRect();
+return {
+    html: e.outerHTML,
+    styles: styles,
+    rect: [rect.x, rect.y, rect.width, rect.height],
+};
+"#;
+
+/// Returns the bounding box of the element `arguments[0]`, relative to the document and in whole
+/// CSS pixels, expanded by `arguments[1]` on every side and clamped to the page.
+const PADDED_RECT_JS: &str = r#"
+const rect = arguments[0].getBoundingClientRect();
+const padding = arguments[1];
+const root = document.documentElement;
+const pageWidth = Math.max(root.scrollWidth, document.body ? document.body.scrollWidth : 0);
+const pageHeight = Math.max(root.scrollHeight, document.body ? document.body.scrollHeight : 0);
+const left = Math.max(0, Math.floor(rect.left + window.scrollX) - padding);
+const top = Math.max(0, Math.floor(rect.top + window.scrollY) - padding);
+const right = Math.min(pageWidth, Math.ceil(rect.right + window.scrollX) + padding);
+const bottom = Math.min(pageHeight, Math.ceil(rect.bottom + window.scrollY) + padding);
+return { x: left, y: top, width: right - left, height: bottom - top };
+"#;
+
+/// Returns `false` while the page marked by [`Form::submit_and_wait`] is still there, and `true`
+/// once it has been replaced by a page that has finished loading (or `null` until then).
+const LEFT_FORM_PAGE_JS: &str = r#"
+if (window.__fantoccini_form_page) {
+    return false;
+}
+return document.readyState === 'complete' ? true : null;
+"#;
+
+/// Returns whether the element `arguments[0]` is in the viewport, entirely, or (if `arguments[1]`)
+/// partially.
+const IN_VIEWPORT_JS: &str = r#"
+const rect = arguments[0].getBoundingClientRect();
+const width = window.innerWidth || document.documentElement.clientWidth;
+const height = window.innerHeight || document.documentElement.clientHeight;
+if (rect.width === 0 && rect.height === 0) {
+    return false;
+}
+if (arguments[1]) {
+    return rect.right > 0 && rect.bottom > 0 && rect.left < width && rect.top < height;
+}
+return rect.left >= 0 && rect.top >= 0 && rect.right <= width && rect.bottom <= height;
+"#;
+
+/// Returns a CSS selector that uniquely matches the element `arguments[0]`, made up of the path to
+/// it from its closest ancestor with a unique id (or from the root element).
+const CSS_PATH_JS: &str = r#"
+const steps = [];
+for (let e = arguments[0]; e; e = e.parentElement) {
+    if (e.id && document.querySelectorAll('#' + CSS.escape(e.id)).length === 1) {
+        steps.unshift('#' + CSS.escape(e.id));
+        break;
+    }
+    let step = CSS.escape(e.localName);
+    const siblings = e.parentElement ? Array.from(e.parentElement.children) : [];
+    if (siblings.some((s) => s !== e && s.localName === e.localName)) {
+        step += ':nth-child(' + (siblings.indexOf(e) + 1) + ')';
+    }
+    steps.unshift(step);
+}
+return steps.join(' > ');
+"#;
+
+/// Dispatches an event of type `arguments[1]` on the element `arguments[0]`, which bubbles if
+/// `arguments[2]`, using the constructor that browsers use for events of that type.
+const DISPATCH_EVENT_JS: &str = r#"
+const [target, type, bubbles] = arguments;
+const constructors = [
+    [/^(before)?input$/, InputEvent],
+    [/^(focus|blur|focusin|focusout)$/, FocusEvent],
+    [/^key(down|up|press)$/, KeyboardEvent],
+    [/^(click|dblclick|contextmenu|mouse(down|up|over|out|enter|leave|move))$/, MouseEvent],
+];
+const [, EventType] = constructors.find(([pattern]) => pattern.test(type)) || [null, Event];
+target.dispatchEvent(new EventType(type, { bubbles }));
+"#;
+
+/// Dispatches the events of dropping a file named `arguments[1]`, with the base64-encoded contents
+/// `arguments[2]`, onto the element `arguments[0]`.
+const DROP_FILE_JS: &str = r#"
+const target = arguments[0];
+const bytes = atob(arguments[2]);
+const data = new Uint8Array(bytes.length);
+for (let i = 0; i < bytes.length; i++) {
+    data[i] = bytes.charCodeAt(i);
+}
+const transfer = new DataTransfer();
+transfer.items.add(new File([data], arguments[1]));
+for (const type of ["dragenter", "dragover", "drop"]) {
+    target.dispatchEvent(new DragEvent(type, {
+        bubbles: true,
+        cancelable: true,
+        dataTransfer: transfer,
+    }));
+}
+"#;
+
+/// Sets the `value` of the element `arguments[0]` to `arguments[1]`, and dispatches `input` and
+/// `change` events for it.
+///
+/// The value is set through the setter of the element's prototype, rather than by assigning to
+/// `value` on the element, since frameworks like React override the latter to track changes.
+const SET_VALUE_JS: &str = r#"
+const e = arguments[0];
+const setter = Object.getOwnPropertyDescriptor(Object.getPrototypeOf(e), "value").set;
+setter.call(e, arguments[1]);
+e.dispatchEvent(new Event("input", { bubbles: true }));
+e.dispatchEvent(new Event("change", { bubbles: true }));
+"#;
+
+fn serialize_base64<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&base64::encode(bytes))
+}
+
 impl Element {
     /// Get back the [`Client`] hosting this `Element`.
     pub fn client(self) -> Client {
@@ -69,7 +240,7 @@ impl Element {
             .client
             .issue(WebDriverCommand::FindElementElement(
                 self.element.clone(),
-                search.into(),
+                self.client.locator(search),
             ))
             .await?;
         let e = self.client.parse_lookup(res)?;
@@ -90,7 +261,7 @@ impl Element {
             .client
             .issue(WebDriverCommand::FindElementElements(
                 self.element.clone(),
-                search.into(),
+                self.client.locator(search),
             ))
             .await?;
         let array = self.client.parse_lookup_all(res)?;
@@ -108,6 +279,10 @@ impl Element {
 impl Element {
     /// Look up an [attribute] value for this element by name.
     ///
+    /// Attributes are what is written in the HTML markup (or set with `setAttribute`), so they
+    /// hold the state the element started out with. For the element's current state, such as
+    /// what has been typed into an `<input>` since, see [`Element::prop`].
+    ///
     /// `Ok(None)` is returned if the element does not have the given attribute.
     ///
     /// See [13.2 Get Element Attribute](https://www.w3.org/TR/webdriver1/#get-element-attribute)
@@ -127,6 +302,13 @@ impl Element {
 
     /// Look up a DOM [property] for this element by name.
     ///
+    /// Properties are the live state of the element object in the page, which scripts and user
+    /// interaction change. Many attributes have a property of the same name, but the two can
+    /// disagree: after typing into `<input value="a">`, the `value` attribute is still `"a"`,
+    /// while the `value` property holds what was typed. Properties also need not be strings, so
+    /// numbers and booleans are turned into strings (such as `"3"` or `"true"`), and objects and
+    /// arrays into JSON.
+    ///
     /// `Ok(None)` is returned if the element does not have the given property.
     ///
     /// See [13.3 Get Element Property](https://www.w3.org/TR/webdriver1/#get-element-property)
@@ -139,11 +321,44 @@ impl Element {
         match self.client.issue(cmd).await? {
             Json::String(v) => Ok(Some(v)),
             Json::Null => Ok(None),
+            v @ Json::Bool(_) | v @ Json::Number(_) => Ok(Some(v.to_string())),
+            v => Ok(Some(serde_json::to_string(&v)?)),
+        }
+    }
+
+    /// Look up the computed value of a CSS property for this element by name, such as
+    /// `"display"` or `"background-color"`.
+    ///
+    /// This is the value after all stylesheets have been applied, as reported by
+    /// `getComputedStyle`, so colors come back as `rgb(..)` or `rgba(..)` and lengths in pixels.
+    ///
+    /// See [13.4 Get Element CSS Value](https://www.w3.org/TR/webdriver1/#get-element-css-value)
+    /// of the WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Get Element CSS Value"))]
+    pub async fn css_value(&mut self, property: &str) -> Result<String, error::CmdError> {
+        let cmd = WebDriverCommand::GetCSSValue(self.element.clone(), property.to_string());
+        match self.client.issue(cmd).await? {
+            Json::String(v) => Ok(v),
             v => Err(error::CmdError::NotW3C(v)),
         }
     }
 
-    /// Retrieve the text contents of this elment.
+    /// Get the current value of a form control, such as an `<input>`, `<select>`, or
+    /// `<textarea>`.
+    ///
+    /// This reads the `value` property of the element, which reflects what the user has typed or
+    /// selected, rather than the `value` attribute, which only holds the value the control
+    /// started out with. `Ok(None)` is returned if the element has no `value` property.
+    pub async fn value(&mut self) -> Result<Option<String>, error::CmdError> {
+        self.prop("value").await
+    }
+
+    /// Retrieve the text contents of this element.
+    ///
+    /// This is the text as it is rendered, much like the element's `innerText`: whitespace is
+    /// collapsed as it would be on screen, text in hidden elements is left out, and `<br>`s and
+    /// block elements become line breaks. It is not the raw `textContent` of the element; use
+    /// [`Element::prop`] with `"textContent"` for that.
     ///
     /// See [13.5 Get Element Text](https://www.w3.org/TR/webdriver1/#get-element-text)
     /// of the WebDriver standard.
@@ -156,6 +371,21 @@ impl Element {
         }
     }
 
+    /// Get the tag name of this element, such as `"div"` or `"input"`.
+    ///
+    /// For HTML documents, browsers report tag names in lowercase.
+    ///
+    /// See [13.6 Get Element Tag Name](https://www.w3.org/TR/webdriver1/#get-element-tag-name)
+    /// of the WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Get Element Tag Name"))]
+    pub async fn tag_name(&mut self) -> Result<String, error::CmdError> {
+        let cmd = WebDriverCommand::GetElementTagName(self.element.clone());
+        match self.client.issue(cmd).await? {
+            Json::String(v) => Ok(v),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
     /// Retrieve the HTML contents of this element.
     ///
     /// `inner` dictates whether the wrapping node's HTML is excluded or not. For example, take the
@@ -173,6 +403,188 @@ impl Element {
         let prop = if inner { "innerHTML" } else { "outerHTML" };
         Ok(self.prop(prop).await?.unwrap())
     }
+
+    /// Get a CSS selector that uniquely matches this element.
+    ///
+    /// The selector follows the path down to this element from its closest ancestor (or itself)
+    /// with an id that is unique on the page, or from the root element if there is none, using
+    /// `:nth-child` wherever a tag name alone is ambiguous; for example,
+    /// `#people > tbody > tr:nth-child(2) > td:nth-child(2)`. This is useful for reporting which
+    /// element a test found, or for recording a selector to use later.
+    ///
+    /// The selector is only guaranteed to be unique at the time it is generated. Since it is tied
+    /// to the structure of the page, it may match a different element, or none at all, once the
+    /// DOM changes.
+    pub async fn css_path(&mut self) -> Result<String, error::CmdError> {
+        let args = vec![via_json!(&self.element)];
+        match self.client.execute(CSS_PATH_JS, args).await? {
+            Json::String(path) => Ok(path),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Get the position of the top-left corner of this element relative to the top-left corner of
+    /// the document, in CSS pixels.
+    ///
+    /// Unlike the [`getBoundingClientRect`] position of the element, which is relative to the
+    /// viewport and so shifts as the page is scrolled, the position returned here stays the same
+    /// no matter how the page is scrolled. This makes it suitable for comparing element positions
+    /// across scroll states, or for locating the element in a full-page screenshot.
+    ///
+    /// [`getBoundingClientRect`]: https://developer.mozilla.org/en-US/docs/Web/API/Element/getBoundingClientRect
+    pub async fn location_in_page(&mut self) -> Result<(f64, f64), error::CmdError> {
+        let args = vec![via_json!(&self.element)];
+        let pos = self
+            .client
+            .execute(
+                "const rect = arguments[0].getBoundingClientRect(); \
+                 return [rect.left + window.scrollX, rect.top + window.scrollY];",
+                args,
+            )
+            .await?;
+        match pos.as_array().map(|pos| &pos[..]) {
+            Some([x, y]) => match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => Ok((x, y)),
+                _ => Err(error::CmdError::NotW3C(pos)),
+            },
+            _ => Err(error::CmdError::NotW3C(pos)),
+        }
+    }
+
+    /// Get the `(width, height)` of this element's content, including any content that overflows
+    /// the element and is only visible by scrolling it, in CSS pixels.
+    ///
+    /// These are the element's [`scrollWidth`] and [`scrollHeight`]. When they are larger than
+    /// its [`client_size`](Element::client_size), the element's content does not fit in it, which
+    /// can't be seen from the element's rectangle alone. If the element is no longer on the page,
+    /// [`StaleElementReference`](error::CmdError::StaleElementReference) is returned.
+    ///
+    /// [`scrollWidth`]: https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollWidth
+    /// [`scrollHeight`]: https://developer.mozilla.org/en-US/docs/Web/API/Element/scrollHeight
+    pub async fn scroll_size(&mut self) -> Result<(f64, f64), error::CmdError> {
+        self.size_props("scrollWidth", "scrollHeight").await
+    }
+
+    /// Get the `(width, height)` of the inside of this element, in CSS pixels.
+    ///
+    /// These are the element's [`clientWidth`] and [`clientHeight`], which include its padding,
+    /// but not its borders, margins, or scrollbars. If the element is no longer on the page,
+    /// [`StaleElementReference`](error::CmdError::StaleElementReference) is returned.
+    ///
+    /// [`clientWidth`]: https://developer.mozilla.org/en-US/docs/Web/API/Element/clientWidth
+    /// [`clientHeight`]: https://developer.mozilla.org/en-US/docs/Web/API/Element/clientHeight
+    pub async fn client_size(&mut self) -> Result<(f64, f64), error::CmdError> {
+        self.size_props("clientWidth", "clientHeight").await
+    }
+
+    /// Whether this element is within the visible part of the page (the viewport).
+    ///
+    /// With `partially` set, any overlap between the element's bounding box and the viewport
+    /// counts; otherwise the element must be entirely inside it. An element can be displayed but
+    /// scrolled out of view, or in view but invisible, so this is not the same as the element
+    /// being displayed; nor does it check whether other elements cover it. Elements that take
+    /// up no space are never in the viewport. If the element is no longer on the page,
+    /// [`StaleElementReference`](error::CmdError::StaleElementReference) is returned.
+    pub async fn is_in_viewport(&mut self, partially: bool) -> Result<bool, error::CmdError> {
+        let args = vec![via_json!(&self.element), Json::Bool(partially)];
+        match self.client.execute(IN_VIEWPORT_JS, args).await? {
+            Json::Bool(b) => Ok(b),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Whether this element is selected, such as a checked checkbox or radio button, or a
+    /// selected `<option>`.
+    ///
+    /// See [13.1 Is Element Selected](https://www.w3.org/TR/webdriver1/#is-element-selected) of
+    /// the WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Is Element Selected"))]
+    pub async fn is_selected(&mut self) -> Result<bool, error::CmdError> {
+        let cmd = WebDriverCommand::IsSelected(self.element.clone());
+        match self.client.issue(cmd).await? {
+            Json::Bool(v) => Ok(v),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Whether this element is enabled, which form controls are unless they are `disabled`
+    /// (directly, or through a disabled `<fieldset>`).
+    ///
+    /// See [13.8 Is Element Enabled](https://www.w3.org/TR/webdriver1/#is-element-enabled) of
+    /// the WebDriver standard.
+    #[cfg_attr(docsrs, doc(alias = "Is Element Enabled"))]
+    pub async fn is_enabled(&mut self) -> Result<bool, error::CmdError> {
+        let cmd = WebDriverCommand::IsEnabled(self.element.clone());
+        match self.client.issue(cmd).await? {
+            Json::Bool(v) => Ok(v),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Whether this element is displayed on the page.
+    ///
+    /// The W3C standard leaves out a command for this, so it is checked with a script instead:
+    /// the element is displayed if it is attached to the document, takes up space on the page,
+    /// and is not `visibility: hidden`. This is the same check as
+    /// [`Client::wait_for_not_displayed`] makes. The element may still be scrolled out of view (see [`Element::is_in_viewport`]),
+    /// or covered by other elements.
+    pub async fn is_displayed(&mut self) -> Result<bool, error::CmdError> {
+        let args = vec![via_json!(&self.element)];
+        match self
+            .client
+            .execute(crate::client::ANY_DISPLAYED_JS, args)
+            .await?
+        {
+            Json::Bool(v) => Ok(v),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    /// Whether `other` is this element or one of its descendants.
+    ///
+    /// This confirms the structure of the page for two elements that have already been found,
+    /// such as that an error message is inside a particular form, which a CSS selector can only
+    /// approximate. It uses [`Node.contains`]. If either element is no longer on the page,
+    /// [`StaleElementReference`](error::CmdError::StaleElementReference) is returned.
+    ///
+    /// [`Node.contains`]: https://developer.mozilla.org/en-US/docs/Web/API/Node/contains
+    pub async fn contains(&mut self, other: &Element) -> Result<bool, error::CmdError> {
+        let args = vec![via_json!(&self.element), via_json!(&other.element)];
+        match self
+            .client
+            .execute("return arguments[0].contains(arguments[1]);", args)
+            .await?
+        {
+            Json::Bool(v) => Ok(v),
+            v => Err(error::CmdError::NotW3C(v)),
+        }
+    }
+
+    async fn size_props(
+        &mut self,
+        width: &str,
+        height: &str,
+    ) -> Result<(f64, f64), error::CmdError> {
+        let args = vec![
+            via_json!(&self.element),
+            Json::from(width),
+            Json::from(height),
+        ];
+        let size = self
+            .client
+            .execute(
+                "return [arguments[0][arguments[1]], arguments[0][arguments[2]]];",
+                args,
+            )
+            .await?;
+        match size.as_array().map(|size| &size[..]) {
+            Some([w, h]) => match (w.as_f64(), h.as_f64()) {
+                (Some(w), Some(h)) => Ok((w, h)),
+                _ => Err(error::CmdError::NotW3C(size)),
+            },
+            _ => Err(error::CmdError::NotW3C(size)),
+        }
+    }
 }
 
 /// [Element Interaction](https://www.w3.org/TR/webdriver1/#element-interaction)
@@ -235,8 +647,269 @@ impl Element {
     }
 }
 
+/// [Actions](https://www.w3.org/TR/webdriver1/#actions)
+impl Element {
+    /// Simulate the user moving the mouse over this element, without clicking it.
+    ///
+    /// The pointer is moved to the center of the element, which fires `mouseover`, `mouseenter`,
+    /// and `mousemove` events and makes the element match `:hover`. This is what you want for menus
+    /// and tooltips that only show up on hover. If the element cannot be reached by the pointer,
+    /// for example because it is hidden, [`error::CmdError::ElementNotInteractable`] is returned.
+    ///
+    /// See [17.5 Perform Actions](https://www.w3.org/TR/webdriver1/#perform-actions) of the
+    /// WebDriver standard.
+    pub async fn hover(&mut self) -> Result<(), error::CmdError> {
+        let actions = vec![self.move_to_center()];
+        self.perform_mouse_actions(actions).await
+    }
+
+    /// Press the mouse button down on the center of this element, hold it for `duration`, and
+    /// then release it.
+    ///
+    /// This is for long-press menus, and for interactions that only start once the button has
+    /// been held for a while, such as some drag-and-drop implementations. The hold is a pause in
+    /// the chain of actions performed by the browser, so the page sees the button held down for
+    /// the full `duration`, regardless of any latency between fantoccini and the browser.
+    ///
+    /// See [17.5 Perform Actions](https://www.w3.org/TR/webdriver1/#perform-actions) of the
+    /// WebDriver standard.
+    pub async fn long_press(&mut self, duration: Duration) -> Result<(), error::CmdError> {
+        let actions = vec![
+            self.move_to_center(),
+            PointerActionItem::Pointer(PointerAction::Down(PointerDownAction { button: 0 })),
+            PointerActionItem::General(GeneralAction::Pause(PauseAction {
+                duration: Some(duration.as_millis() as u64),
+            })),
+            PointerActionItem::Pointer(PointerAction::Up(PointerUpAction { button: 0 })),
+        ];
+        self.perform_mouse_actions(actions).await
+    }
+
+    /// The action of moving the pointer to the center of this element.
+    fn move_to_center(&self) -> PointerActionItem {
+        PointerActionItem::Pointer(PointerAction::Move(PointerMoveAction {
+            duration: None,
+            // offsets are relative to the center of the element
+            origin: PointerOrigin::Element(self.element.clone()),
+            x: Some(0),
+            y: Some(0),
+        }))
+    }
+
+    /// Perform the given actions with the mouse.
+    async fn perform_mouse_actions(
+        &mut self,
+        actions: Vec<PointerActionItem>,
+    ) -> Result<(), error::CmdError> {
+        let actions = ActionSequence {
+            id: String::from("mouse"),
+            actions: ActionsType::Pointer {
+                parameters: PointerActionParameters {
+                    pointer_type: PointerType::Mouse,
+                },
+                actions,
+            },
+        };
+        self.client
+            .perform_actions(ActionsParameters {
+                actions: vec![actions],
+            })
+            .await
+    }
+
+    /// Focus this element, and then perform the given sequence of key presses on it.
+    ///
+    /// Unlike [`Element::send_keys`], the whole sequence is performed as a single chain of
+    /// actions, and can mix text with special keys and keyboard shortcuts; see [`KeySequence`].
+    ///
+    /// See [17.5 Perform Actions](https://www.w3.org/TR/webdriver1/#perform-actions) of the
+    /// WebDriver standard.
+    pub async fn send_key_sequence(&mut self, keys: KeySequence) -> Result<(), error::CmdError> {
+        let args = vec![via_json!(&self.element)];
+        self.client.execute("arguments[0].focus();", args).await?;
+        self.client.send_key_sequence(keys).await
+    }
+
+    /// Focus this element, and paste the contents of the clipboard into it.
+    ///
+    /// This presses the platform's paste shortcut (`Cmd+V` if the browser is running on macOS,
+    /// and `Ctrl+V` otherwise), so the page sees a real `paste` event, unlike when typing the
+    /// text with [`Element::send_keys`]. The platform is that of the browser rather than of this
+    /// program, as reported by the page's `navigator.platform`. Use
+    /// [`Client::write_clipboard`](crate::Client::write_clipboard) to set what is pasted.
+    pub async fn paste(&mut self) -> Result<(), error::CmdError> {
+        let is_mac = self
+            .client
+            .execute(
+                "return /^(Mac|iPhone|iPad)/.test(navigator.platform);",
+                vec![],
+            )
+            .await?;
+        let modifier = if is_mac == Json::Bool(true) {
+            Key::Meta
+        } else {
+            Key::Control
+        };
+        self.send_key_sequence(KeySequence::new().chord(&[modifier, Key::Char('v')]))
+            .await
+    }
+}
+
+/// Operations that wait for a change on the page.
+impl Element {
+    /// Wait until the given attribute of this element satisfies `predicate`.
+    ///
+    /// The attribute is read every 100ms and passed to `predicate` (as `None` if the element does
+    /// not have the attribute), until `predicate` returns `true`. The attribute value that
+    /// satisfied the predicate is then returned. This is a precise way to wait for widgets that
+    /// expose their state through attributes, such as `aria-expanded="true"` or
+    /// `data-state="loaded"`.
+    ///
+    /// If the element goes stale while waiting, for example because the page re-rendered it, it
+    /// is treated as not ready, since this handle will never see the attribute change again; find
+    /// the element anew to wait on its replacement. If `predicate` is not satisfied within
+    /// `timeout`, [`error::CmdError::WaitTimeout`] is returned.
+    pub async fn wait_for_attribute<F>(
+        &mut self,
+        attribute: &str,
+        predicate: F,
+        timeout: Duration,
+    ) -> Result<Option<String>, error::CmdError>
+    where
+        F: Fn(Option<&str>) -> bool,
+    {
+        let start = Instant::now();
+        loop {
+            match self.attr(attribute).await {
+                Ok(value) if predicate(value.as_deref()) => return Ok(value),
+                Ok(_)
+                | Err(error::CmdError::NoSuchElement(_))
+                | Err(error::CmdError::StaleElementReference(_)) => {}
+                Err(e) => return Err(e),
+            }
+
+            crate::client::wait_for_next_poll(start, timeout).await?;
+        }
+    }
+}
+
 /// Higher-level operations.
 impl Element {
+    /// Get a PNG-encoded screenshot of this element with `padding` CSS pixels of its
+    /// surroundings on every side.
+    ///
+    /// Where [`Client::screenshot_element`] captures exactly the element's bounds, this frames it
+    /// with some context, such as for documentation. The padded rectangle is clamped to the page,
+    /// so there is less padding on the sides where the element is close to the edge of the page.
+    /// It is captured with [`Client::screenshot_region`], and is subject to the same limitations.
+    pub async fn screenshot_with_padding(
+        &mut self,
+        padding: u32,
+    ) -> Result<Vec<u8>, error::CmdError> {
+        let args = vec![via_json!(&self.element), Json::from(padding)];
+        let region = self.client.execute(PADDED_RECT_JS, args).await?;
+        let field = |key: &str| region[key].as_u64().map(|v| v as u32);
+        match (field("x"), field("y"), field("width"), field("height")) {
+            (Some(x), Some(y), Some(width), Some(height)) => {
+                self.client.screenshot_region(x, y, width, height).await
+            }
+            _ => Err(error::CmdError::NotW3C(region)),
+        }
+    }
+
+    /// Take a self-contained snapshot of this element for debugging, such as when a visual test
+    /// fails.
+    ///
+    /// The snapshot holds the element's outer HTML, all of its computed styles, its bounding
+    /// box, and a screenshot of it. The first three are gathered with a single script, and the
+    /// screenshot with one more command.
+    pub async fn debug_snapshot(&mut self) -> Result<ElementSnapshot, error::CmdError> {
+        let args = vec![via_json!(&self.element)];
+        let res = self.client.execute(SNAPSHOT_JS, args).await?;
+        let page: PageSnapshot = serde_json::from_value(res)?;
+        let screenshot = self.client.screenshot_element(self.clone()).await?;
+        Ok(ElementSnapshot {
+            outer_html: page.html,
+            styles: page.styles,
+            rect: page.rect,
+            screenshot,
+        })
+    }
+
+    /// Drop the file at `path` onto this element, as if it had been dragged there from the
+    /// user's file manager.
+    ///
+    /// Some upload widgets only accept files through drag and drop, and have no `<input
+    /// type="file">` to send the file's path to. WebDriver cannot drag files from outside the
+    /// browser, so this simulates it instead: the file is read here, and a script builds a
+    /// [`DataTransfer`] holding it, and dispatches `dragenter`, `dragover`, and `drop` events
+    /// carrying that transfer on the element. Since the events are synthetic, they skip
+    /// everything a real drag does before reaching the page (and are not "trusted"), but most
+    /// drop zones handle them just the same.
+    ///
+    /// The dropped file has the same name as the file at `path`, and no MIME type.
+    ///
+    /// [`DataTransfer`]: https://developer.mozilla.org/en-US/docs/Web/API/DataTransfer
+    pub async fn drop_file(&mut self, path: &std::path::Path) -> Result<(), error::CmdError> {
+        let invalid_path = |msg: String| error::CmdError::InvalidArgument("path".to_string(), msg);
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| invalid_path(format!("{} has no file name", path.display())))?;
+        let contents = std::fs::read(path)
+            .map_err(|e| invalid_path(format!("could not read {}: {}", path.display(), e)))?;
+        let args = vec![
+            via_json!(&self.element),
+            Json::from(name),
+            Json::from(base64::encode(contents)),
+        ];
+        self.client.execute(DROP_FILE_JS, args).await?;
+        Ok(())
+    }
+
+    /// Set the value of this form control to `value` directly, without typing it.
+    ///
+    /// This is much faster than [`Element::send_keys`] for filling in many fields, since the
+    /// value is set by a single script rather than one key press at a time. The script then
+    /// dispatches `input` and `change` events on the element, so that frameworks with data
+    /// bindings, such as React's controlled inputs or Vue's `v-model`, pick up the new value.
+    ///
+    /// No keyboard events are fired, so `keydown`, `keypress`, and `keyup` handlers will not run,
+    /// and any existing value is replaced rather than appended to. Use [`Element::send_keys`]
+    /// when the page relies on those.
+    pub async fn set_value(&mut self, value: &str) -> Result<(), error::CmdError> {
+        let args = vec![via_json!(&self.element), Json::from(value)];
+        self.client.execute(SET_VALUE_JS, args).await?;
+        Ok(())
+    }
+
+    /// Dispatch a synthetic DOM event of the given type on this element, such as `"change"`,
+    /// `"blur"`, or a custom event the page listens for.
+    ///
+    /// This is an escape hatch for when a page, or the framework it uses, waits for an event that
+    /// the interactions available through WebDriver do not produce. The event is created with the
+    /// constructor browsers use for its type (such as `InputEvent` for `"input"`, and
+    /// `FocusEvent` for `"blur"`), or a plain `Event` for other types, and bubbles up the DOM if
+    /// `bubbles` is set.
+    ///
+    /// Only the event is dispatched: no default action takes place, so, for example, a
+    /// synthetic `"click"` does not follow a link. Synthetic events also have `isTrusted` set to
+    /// `false`, which some handlers check to ignore them. Prefer the real interactions, like
+    /// [`Element::click`] and [`Element::send_keys`], where they will do.
+    pub async fn dispatch_event(
+        &mut self,
+        event_type: &str,
+        bubbles: bool,
+    ) -> Result<(), error::CmdError> {
+        let args = vec![
+            via_json!(&self.element),
+            Json::from(event_type),
+            Json::from(bubbles),
+        ];
+        self.client.execute(DISPATCH_EVENT_JS, args).await?;
+        Ok(())
+    }
+
     /// Follow the `href` target of the element matching the given CSS selector *without* causing a
     /// click interaction.
     ///
@@ -306,6 +979,45 @@ impl Element {
         let locator = format!(r".//option[.='{}']", label);
         self.find(Locator::XPath(&locator)).await?.click().await
     }
+
+    /// Get the `value`s of the currently selected `<option>`s of this `<select>`, in document
+    /// order.
+    ///
+    /// This is mostly useful for `<select multiple>`, where any number of options can be
+    /// selected at once. If this element is not a `<select>`,
+    /// [`CmdError::InvalidArgument`](error::CmdError::InvalidArgument) is returned.
+    pub async fn selected_options(&mut self) -> Result<Vec<String>, error::CmdError> {
+        self.selected_options_by("value").await
+    }
+
+    /// Get the visible labels of the currently selected `<option>`s of this `<select>`, in
+    /// document order.
+    ///
+    /// See [`Element::selected_options`].
+    pub async fn selected_texts(&mut self) -> Result<Vec<String>, error::CmdError> {
+        self.selected_options_by("text").await
+    }
+
+    /// Get the given property of each of the selected options of this `<select>`.
+    async fn selected_options_by(&mut self, prop: &str) -> Result<Vec<String>, error::CmdError> {
+        let args = vec![via_json!(&self.element), Json::from(prop)];
+        let res = self
+            .client
+            .execute(
+                "const [e, prop] = arguments; \
+                 if (!(e instanceof HTMLSelectElement)) { return null; } \
+                 return Array.from(e.selectedOptions, (o) => o[prop]);",
+                args,
+            )
+            .await?;
+        match res {
+            Json::Null => Err(error::CmdError::InvalidArgument(
+                "self".to_string(),
+                "the element is not a <select>".to_string(),
+            )),
+            v => Ok(serde_json::from_value(v)?),
+        }
+    }
 }
 
 impl Form {
@@ -322,7 +1034,8 @@ impl Form {
         locator: Locator<'_>,
         value: &str,
     ) -> Result<Self, error::CmdError> {
-        let locator = WebDriverCommand::FindElementElement(self.form.clone(), locator.into());
+        let locator =
+            WebDriverCommand::FindElementElement(self.form.clone(), self.client.locator(locator));
         let value = Json::from(value);
 
         let res = self.client.issue(locator).await?;
@@ -369,7 +1082,7 @@ impl Form {
     ///
     /// `false` is returned if a matching button was not found.
     pub async fn submit_with(mut self, button: Locator<'_>) -> Result<Client, error::CmdError> {
-        let locator = WebDriverCommand::FindElementElement(self.form, button.into());
+        let locator = WebDriverCommand::FindElementElement(self.form, self.client.locator(button));
         let res = self.client.issue(locator).await?;
         let submit = self.client.parse_lookup(res)?;
         let res = self
@@ -470,4 +1183,67 @@ impl Form {
             Err(error::CmdError::NotW3C(res))
         }
     }
+
+    /// Submit this form using the first available submit button, and wait to see what happens.
+    ///
+    /// Submitting a form either leads to another page, or leaves the browser on the same page
+    /// with some sign of the result, such as a validation error or a success message. This waits
+    /// for either: if the browser leaves the page and finishes loading the next one,
+    /// [`SubmitOutcome::Navigated`] is returned with the new URL; if instead one of the given
+    /// `conditions` is met on the page the form is on, [`SubmitOutcome::Condition`] is returned
+    /// with its index, as with [`Client::wait_for_any`]. If neither happens within `timeout`,
+    /// [`CmdError::WaitTimeout`](error::CmdError::WaitTimeout) is returned.
+    ///
+    /// Navigation is told apart from staying put by marking the page before submitting, so a
+    /// form that posts back to the same URL still counts as having navigated.
+    ///
+    /// ```no_run
+    /// # use fantoccini::{Client, Condition, Locator};
+    /// # use fantoccini::elements::SubmitOutcome;
+    /// # use std::time::Duration;
+    /// # async fn example(mut c: Client) -> Result<(), fantoccini::error::CmdError> {
+    /// let mut form = c.form(Locator::Id("login")).await?;
+    /// form.set_by_name("password", "hunter2").await?;
+    /// let errors = [Condition::Displayed(Locator::Css(".error"))];
+    /// match form.submit_and_wait(&errors, Duration::from_secs(10)).await? {
+    ///     (_, SubmitOutcome::Navigated(url)) => println!("logged in, now at {}", url),
+    ///     (_, SubmitOutcome::Condition(_)) => println!("the login was rejected"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn submit_and_wait(
+        mut self,
+        conditions: &[Condition<'_>],
+        timeout: Duration,
+    ) -> Result<(Client, SubmitOutcome), error::CmdError> {
+        self.client
+            .execute("window.__fantoccini_form_page = true;", vec![])
+            .await?;
+        let mut client = self.submit().await?;
+
+        let start = Instant::now();
+        loop {
+            // the page may be in the middle of being replaced, which makes scripts fail
+            match client.execute(LEFT_FORM_PAGE_JS, vec![]).await {
+                Ok(Json::Bool(true)) => {
+                    let url = client.current_url().await?;
+                    return Ok((client, SubmitOutcome::Navigated(url)));
+                }
+                Ok(Json::Bool(false)) => {
+                    for (i, condition) in conditions.iter().enumerate() {
+                        if client.is_met(condition).await? {
+                            return Ok((client, SubmitOutcome::Condition(i)));
+                        }
+                    }
+                }
+                Ok(_)
+                | Err(error::CmdError::Standard(..))
+                | Err(error::CmdError::JavascriptError { .. }) => {}
+                Err(e) => return Err(e),
+            }
+
+            crate::client::wait_for_next_poll(start, timeout).await?;
+        }
+    }
 }
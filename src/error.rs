@@ -62,7 +62,8 @@ pub enum CmdError {
     /// A standard WebDriver error occurred.
     ///
     /// See [the spec] for details about what each of these errors represent. Note that for
-    /// convenience `NoSuchElement` has been extracted into its own top-level variant.
+    /// convenience `NoSuchElement`, `StaleElementReference`, and `ElementNotInteractable` have
+    /// been extracted into their own top-level variants.
     ///
     /// [the spec]: https://www.w3.org/TR/webdriver/#handling-errors
     Standard(wderror::WebDriverError),
@@ -72,12 +73,42 @@ pub enum CmdError {
     /// This variant lifts the ["no such element"] error variant from `Standard` to simplify
     /// checking for it in user code.
     ///
-    /// It is also used for the ["stale element reference"] error variant.
-    ///
     /// ["no such element"]: https://www.w3.org/TR/webdriver/#dfn-no-such-element
-    /// ["stale element reference"]: https://www.w3.org/TR/webdriver/#dfn-stale-element-reference
     NoSuchElement(wderror::WebDriverError),
 
+    /// The element is no longer attached to the page, for example because the page re-rendered
+    /// or navigated away since the element was found.
+    ///
+    /// This variant lifts the ["stale element reference"] error variant from `Standard` to
+    /// simplify checking for it in user code.
+    ///
+    /// ["stale element reference"]: https://www.w3.org/TR/webdriver/#dfn-stale-element-reference
+    StaleElementReference(wderror::WebDriverError),
+
+    /// The element cannot be interacted with, for example because it is hidden or covered by
+    /// another element.
+    ///
+    /// This variant lifts the ["element not interactable"] error variant from `Standard` to
+    /// simplify checking for it in user code.
+    ///
+    /// ["element not interactable"]: https://www.w3.org/TR/webdriver/#dfn-element-not-interactable
+    ElementNotInteractable(wderror::WebDriverError),
+
+    /// A script run in the page threw an exception.
+    ///
+    /// This variant lifts the ["javascript error"] error variant from `Standard` so that the
+    /// stack trace reported by the WebDriver server is easy to get at. Note that the contents of
+    /// `stacktrace` vary between implementations: some report the JavaScript stack, others the
+    /// stack of the driver itself, and some report nothing at all.
+    ///
+    /// ["javascript error"]: https://www.w3.org/TR/webdriver/#dfn-javascript-error
+    JavascriptError {
+        /// The error message, usually including the message of the thrown exception.
+        message: String,
+        /// The stack trace of the error, if any.
+        stacktrace: String,
+    },
+
     /// The requested window does not exist.
     ///
     /// This variant lifts the ["no such window"] error variant from `Standard` to simplify
@@ -86,6 +117,14 @@ pub enum CmdError {
     /// ["no such window"]: https://www.w3.org/TR/webdriver/#dfn-no-such-window
     NoSuchWindow(wderror::WebDriverError),
 
+    /// No cookie with the requested name is visible to the current page.
+    ///
+    /// This variant lifts the ["no such cookie"] error variant from `Standard` to simplify
+    /// checking for it in user code.
+    ///
+    /// ["no such cookie"]: https://www.w3.org/TR/webdriver/#dfn-no-such-cookie
+    NoSuchCookie(wderror::WebDriverError),
+
     /// A bad URL was encountered during parsing.
     ///
     /// This normally happens if a link is clicked or the current URL is requested, but the URL in
@@ -120,6 +159,12 @@ pub enum CmdError {
 
     /// Could not decode a base64 image
     ImageDecodeError(::base64::DecodeError),
+
+    /// Timed out waiting for a condition on the page to be met.
+    WaitTimeout,
+
+    /// The page was loaded, but the server responded with an HTTP error status.
+    HttpStatus(http::StatusCode),
 }
 
 impl CmdError {
@@ -138,6 +183,24 @@ impl CmdError {
     pub fn is_miss(&self) -> bool {
         matches!(self, CmdError::NoSuchElement(..))
     }
+
+    /// Returns true if this error indicates that an operation timed out.
+    ///
+    /// This is the case for [`CmdError::WaitTimeout`], and for the standard WebDriver ["timeout"]
+    /// and ["script timeout"] errors.
+    ///
+    /// ["timeout"]: https://www.w3.org/TR/webdriver/#dfn-timeout
+    /// ["script timeout"]: https://www.w3.org/TR/webdriver/#dfn-script-timeout
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            CmdError::WaitTimeout => true,
+            CmdError::Standard(e) => matches!(
+                e.error,
+                wderror::ErrorStatus::Timeout | wderror::ErrorStatus::ScriptTimeout
+            ),
+            _ => false,
+        }
+    }
 }
 
 impl Error for CmdError {
@@ -145,7 +208,11 @@ impl Error for CmdError {
         match *self {
             CmdError::Standard(..) => "webdriver returned error",
             CmdError::NoSuchElement(..) => "no element found matching selector",
+            CmdError::StaleElementReference(..) => "element is no longer attached to the page",
+            CmdError::ElementNotInteractable(..) => "element cannot be interacted with",
+            CmdError::JavascriptError { .. } => "javascript error in page",
             CmdError::NoSuchWindow(..) => "no window is currently selected",
+            CmdError::NoSuchCookie(..) => "no cookie found with the given name",
             CmdError::BadUrl(..) => "bad url provided",
             CmdError::Failed(..) => "webdriver could not be reached",
             CmdError::Lost(..) => "webdriver connection lost",
@@ -154,6 +221,8 @@ impl Error for CmdError {
             CmdError::NotW3C(..) => "webdriver returned non-conforming response",
             CmdError::InvalidArgument(..) => "invalid argument provided",
             CmdError::ImageDecodeError(..) => "error decoding image",
+            CmdError::WaitTimeout => "timeout waiting on condition",
+            CmdError::HttpStatus(..) => "page returned an http error status",
         }
     }
 
@@ -161,13 +230,21 @@ impl Error for CmdError {
         match *self {
             CmdError::Standard(ref e)
             | CmdError::NoSuchElement(ref e)
-            | CmdError::NoSuchWindow(ref e) => Some(e),
+            | CmdError::StaleElementReference(ref e)
+            | CmdError::ElementNotInteractable(ref e)
+            | CmdError::NoSuchWindow(ref e)
+            | CmdError::NoSuchCookie(ref e) => Some(e),
             CmdError::BadUrl(ref e) => Some(e),
             CmdError::Failed(ref e) => Some(e),
             CmdError::Lost(ref e) => Some(e),
             CmdError::Json(ref e) => Some(e),
             CmdError::ImageDecodeError(ref e) => Some(e),
-            CmdError::NotJson(_) | CmdError::NotW3C(_) | CmdError::InvalidArgument(..) => None,
+            CmdError::JavascriptError { .. }
+            | CmdError::NotJson(_)
+            | CmdError::NotW3C(_)
+            | CmdError::InvalidArgument(..)
+            | CmdError::WaitTimeout
+            | CmdError::HttpStatus(..) => None,
         }
     }
 }
@@ -175,11 +252,29 @@ impl Error for CmdError {
 impl fmt::Display for CmdError {
     #[allow(deprecated)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let CmdError::WaitTimeout = *self {
+            // there are no further details to give
+            return write!(f, "{}", self.description());
+        }
+
         write!(f, "{}: ", self.description())?;
         match *self {
             CmdError::Standard(ref e)
             | CmdError::NoSuchElement(ref e)
-            | CmdError::NoSuchWindow(ref e) => write!(f, "{}", e),
+            | CmdError::StaleElementReference(ref e)
+            | CmdError::ElementNotInteractable(ref e)
+            | CmdError::NoSuchWindow(ref e)
+            | CmdError::NoSuchCookie(ref e) => write!(f, "{}", e),
+            CmdError::JavascriptError {
+                ref message,
+                ref stacktrace,
+            } => {
+                write!(f, "{}", message)?;
+                if !stacktrace.is_empty() {
+                    write!(f, "\n{}", stacktrace)?;
+                }
+                Ok(())
+            }
             CmdError::BadUrl(ref e) => write!(f, "{}", e),
             CmdError::Failed(ref e) => write!(f, "{}", e),
             CmdError::Lost(ref e) => write!(f, "{}", e),
@@ -190,6 +285,8 @@ impl fmt::Display for CmdError {
             CmdError::InvalidArgument(ref arg, ref msg) => {
                 write!(f, "Invalid argument `{}`: {}", arg, msg)
             }
+            CmdError::HttpStatus(ref status) => write!(f, "{}", status),
+            CmdError::WaitTimeout => unreachable!(),
         }
     }
 }
@@ -214,14 +311,16 @@ impl From<HError> for CmdError {
 
 impl From<wderror::WebDriverError> for CmdError {
     fn from(e: wderror::WebDriverError) -> Self {
-        if let wderror::WebDriverError {
-            error: wderror::ErrorStatus::NoSuchElement,
-            ..
-        } = e
-        {
-            CmdError::NoSuchElement(e)
-        } else {
-            CmdError::Standard(e)
+        match e.error {
+            wderror::ErrorStatus::NoSuchElement => CmdError::NoSuchElement(e),
+            wderror::ErrorStatus::StaleElementReference => CmdError::StaleElementReference(e),
+            wderror::ErrorStatus::ElementNotInteractable => CmdError::ElementNotInteractable(e),
+            wderror::ErrorStatus::NoSuchCookie => CmdError::NoSuchCookie(e),
+            wderror::ErrorStatus::JavascriptError => CmdError::JavascriptError {
+                message: e.message.into_owned(),
+                stacktrace: e.stack.into_owned(),
+            },
+            _ => CmdError::Standard(e),
         }
     }
 }
@@ -239,6 +338,34 @@ mod tests {
     #[test]
     fn ensure_display_error_doesnt_stackoverflow() {
         println!("{}", CmdError::NotJson("test".to_string()));
+        println!("{}", CmdError::WaitTimeout);
+        println!(
+            "{}",
+            CmdError::JavascriptError {
+                message: "boom".to_string(),
+                stacktrace: "at <anonymous>:1:7".to_string(),
+            }
+        );
+        println!("{}", CmdError::HttpStatus(http::StatusCode::NOT_FOUND));
         println!("{}", NewSessionError::Lost(IOError::last_os_error()));
     }
+
+    #[test]
+    fn is_timeout() {
+        assert!(CmdError::WaitTimeout.is_timeout());
+        let script = wderror::WebDriverError::new(wderror::ErrorStatus::ScriptTimeout, "slow");
+        assert!(CmdError::from(script).is_timeout());
+        let miss = wderror::WebDriverError::new(wderror::ErrorStatus::NoSuchElement, "gone");
+        assert!(!CmdError::from(miss).is_timeout());
+    }
+
+    #[test]
+    fn stale_element_reference() {
+        let stale =
+            wderror::WebDriverError::new(wderror::ErrorStatus::StaleElementReference, "detached");
+        match CmdError::from(stale) {
+            CmdError::StaleElementReference(e) => assert_eq!(e.message, "detached"),
+            e => panic!("expected StaleElementReference, got {:?}", e),
+        }
+    }
 }
@@ -0,0 +1,189 @@
+//! Keyboard keys, and sequences of key presses to perform in a single action chain.
+
+use webdriver::actions::{
+    ActionSequence, ActionsType, KeyAction, KeyActionItem, KeyDownAction, KeyUpAction,
+};
+use webdriver::command::ActionsParameters;
+
+/// A key on the keyboard.
+///
+/// Keys that do not produce a character are identified by the code points the WebDriver standard
+/// [assigns to them](https://www.w3.org/TR/webdriver1/#keyboard-actions). Any other key is given
+/// as [`Key::Char`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Key {
+    /// The key that produces the given character, such as `Key::Char('a')`.
+    Char(char),
+    /// The Cancel key.
+    Cancel,
+    /// The Help key.
+    Help,
+    /// The Backspace key.
+    Backspace,
+    /// The Tab key.
+    Tab,
+    /// The Clear key.
+    Clear,
+    /// The Return key.
+    Return,
+    /// The Enter key.
+    Enter,
+    /// The Shift key.
+    Shift,
+    /// The Control key.
+    Control,
+    /// The Alt key.
+    Alt,
+    /// The Pause key.
+    Pause,
+    /// The Escape key.
+    Escape,
+    /// The space bar.
+    Space,
+    /// The Page Up key.
+    PageUp,
+    /// The Page Down key.
+    PageDown,
+    /// The End key.
+    End,
+    /// The Home key.
+    Home,
+    /// The left arrow key.
+    Left,
+    /// The up arrow key.
+    Up,
+    /// The right arrow key.
+    Right,
+    /// The down arrow key.
+    Down,
+    /// The Insert key.
+    Insert,
+    /// The Delete key.
+    Delete,
+    /// The given function key, from `F(1)` to `F(12)`.
+    ///
+    /// Other numbers are not valid keys, and are sent as the Null key instead.
+    F(u8),
+    /// The Meta key, which is the Command key on macOS and the Windows key on Windows.
+    Meta,
+}
+
+impl From<Key> for char {
+    fn from(key: Key) -> char {
+        let code = match key {
+            Key::Char(c) => return c,
+            Key::Cancel => 0xE001,
+            Key::Help => 0xE002,
+            Key::Backspace => 0xE003,
+            Key::Tab => 0xE004,
+            Key::Clear => 0xE005,
+            Key::Return => 0xE006,
+            Key::Enter => 0xE007,
+            Key::Shift => 0xE008,
+            Key::Control => 0xE009,
+            Key::Alt => 0xE00A,
+            Key::Pause => 0xE00B,
+            Key::Escape => 0xE00C,
+            Key::Space => 0xE00D,
+            Key::PageUp => 0xE00E,
+            Key::PageDown => 0xE00F,
+            Key::End => 0xE010,
+            Key::Home => 0xE011,
+            Key::Left => 0xE012,
+            Key::Up => 0xE013,
+            Key::Right => 0xE014,
+            Key::Down => 0xE015,
+            Key::Insert => 0xE016,
+            Key::Delete => 0xE017,
+            Key::F(n @ 1..=12) => 0xE030 + u32::from(n),
+            Key::F(_) => 0xE000,
+            Key::Meta => 0xE03D,
+        };
+        std::char::from_u32(code).unwrap()
+    }
+}
+
+/// A sequence of key presses, performed as a single chain of WebDriver
+/// [actions](https://www.w3.org/TR/webdriver1/#actions).
+///
+/// Since the whole sequence is sent to the browser at once, there are no gaps between the key
+/// presses like there are between separate calls to [`Element::send_keys`], and modifier keys
+/// pressed in a [`chord`](KeySequence::chord) are held down for exactly as long as they should be.
+///
+/// ```no_run
+/// # use fantoccini::keys::{Key, KeySequence};
+/// // select everything in the focused input, replace it, and submit
+/// let seq = KeySequence::new()
+///     .chord(&[Key::Control, Key::Char('a')])
+///     .key(Key::Delete)
+///     .type_text("new value")
+///     .key(Key::Enter);
+/// ```
+///
+/// Perform the sequence with [`Client::send_key_sequence`] or [`Element::send_key_sequence`].
+///
+/// [`Client::send_key_sequence`]: crate::Client::send_key_sequence
+/// [`Element::send_keys`]: crate::elements::Element::send_keys
+/// [`Element::send_key_sequence`]: crate::elements::Element::send_key_sequence
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeySequence {
+    /// Whether each key is pressed (`true`) or released, in order.
+    events: Vec<(bool, char)>,
+}
+
+impl KeySequence {
+    /// Start an empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Press and release the given key.
+    pub fn key(mut self, key: Key) -> Self {
+        let c = char::from(key);
+        self.events.push((true, c));
+        self.events.push((false, c));
+        self
+    }
+
+    /// Press all the given keys in order, and then release them in reverse order.
+    ///
+    /// This is how keyboard shortcuts such as `Ctrl+Shift+T` are performed.
+    pub fn chord(mut self, keys: &[Key]) -> Self {
+        self.events
+            .extend(keys.iter().map(|&key| (true, char::from(key))));
+        self.events
+            .extend(keys.iter().rev().map(|&key| (false, char::from(key))));
+        self
+    }
+
+    /// Type the given text, one character at a time.
+    pub fn type_text(mut self, text: &str) -> Self {
+        for c in text.chars() {
+            self.events.push((true, c));
+            self.events.push((false, c));
+        }
+        self
+    }
+
+    pub(crate) fn into_actions(self) -> ActionsParameters {
+        let actions = self
+            .events
+            .into_iter()
+            .map(|(down, c)| {
+                let value = c.to_string();
+                KeyActionItem::Key(if down {
+                    KeyAction::Down(KeyDownAction { value })
+                } else {
+                    KeyAction::Up(KeyUpAction { value })
+                })
+            })
+            .collect();
+        ActionsParameters {
+            actions: vec![ActionSequence {
+                id: String::from("keyboard"),
+                actions: ActionsType::Key { actions },
+            }],
+        }
+    }
+}
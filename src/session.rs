@@ -11,20 +11,93 @@ use std::pin::Pin;
 use std::task::Context;
 use std::task::Poll;
 use tokio::sync::{mpsc, oneshot};
-use webdriver::command::WebDriverCommand;
+use webdriver::command::{AddCookieParameters, WebDriverCommand};
 use webdriver::error::ErrorStatus;
 use webdriver::error::WebDriverError;
 
 type Ack = oneshot::Sender<Result<Json, error::CmdError>>;
 
-type Wcmd = WebDriverCommand<webdriver::command::VoidWebDriverExtensionCommand>;
+type Wcmd = WebDriverCommand<ExtensionCommand>;
+
+/// Commands that are not part of the WebDriver standard, but that some drivers support anyway.
+///
+/// These can be issued as a [`WebDriverCommand::Extension`], such as through
+/// [`Client::issue_nowait`].
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum ExtensionCommand {
+    /// Run a [Chrome DevTools Protocol](https://chromedevtools.github.io/devtools-protocol/)
+    /// command through `chromedriver`.
+    ExecuteCdp {
+        /// The fully-qualified command name, such as `"Browser.getVersion"`.
+        cmd: String,
+        /// The JSON object of parameters the command takes.
+        params: Json,
+    },
+}
+
+impl webdriver::command::WebDriverExtensionCommand for ExtensionCommand {
+    fn parameters_json(&self) -> Option<Json> {
+        match *self {
+            ExtensionCommand::ExecuteCdp {
+                ref cmd,
+                ref params,
+            } => Some(serde_json::json!({ "cmd": cmd, "params": params })),
+        }
+    }
+}
+
+/// Complete the user's capabilities with those fantoccini relies on.
+pub(crate) fn session_capabilities(
+    cap: &webdriver::capabilities::Capabilities,
+) -> webdriver::capabilities::Capabilities {
+    let mut cap = cap.to_owned();
+
+    // https://www.w3.org/TR/webdriver/#capabilities
+    //  - we want the browser to wait for the page to load
+    cap.insert("pageLoadStrategy".to_string(), Json::from("normal"));
+
+    // make chrome comply with w3c
+    cap.entry("goog:chromeOptions".to_string())
+        .or_insert_with(|| Json::Object(serde_json::Map::new()))
+        .as_object_mut()
+        .expect("goog:chromeOptions wasn't a JSON object")
+        .insert("w3c".to_string(), Json::from(true));
+
+    cap
+}
+
+/// Make a copy of the given new session parameters, which do not implement `Clone` themselves.
+fn clone_new_session(
+    params: &webdriver::command::NewSessionParameters,
+) -> webdriver::command::NewSessionParameters {
+    use webdriver::capabilities::{LegacyNewSessionParameters, SpecNewSessionParameters};
+    use webdriver::command::NewSessionParameters;
+    match *params {
+        NewSessionParameters::Spec(ref spec) => {
+            NewSessionParameters::Spec(SpecNewSessionParameters {
+                alwaysMatch: spec.alwaysMatch.clone(),
+                firstMatch: spec.firstMatch.clone(),
+            })
+        }
+        NewSessionParameters::Legacy(ref legacy) => {
+            NewSessionParameters::Legacy(LegacyNewSessionParameters {
+                desired: legacy.desired.clone(),
+                required: legacy.required.clone(),
+            })
+        }
+    }
+}
 
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub(crate) enum Cmd {
     SetUa(String),
     GetSessionId,
+    GetCapabilities,
+    GetFramePath,
     Shutdown,
+    Restart,
     Persist,
     GetUa,
     Raw {
@@ -76,8 +149,58 @@ impl Client {
         }
     }
 
-    pub(crate) fn is_legacy(&self) -> bool {
-        self.is_legacy
+    /// Queue `cmd` for the session without waiting for its result.
+    pub(crate) fn enqueue<C>(&mut self, cmd: C) -> Result<(), error::CmdError>
+    where
+        C: Into<Cmd>,
+    {
+        // the session ignores acks that nobody is waiting for
+        let (tx, _) = oneshot::channel();
+        self.tx
+            .send(Task {
+                request: cmd.into(),
+                ack: tx,
+            })
+            .map_err(|_| {
+                error::CmdError::Lost(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "WebDriver session has been closed",
+                ))
+            })
+    }
+}
+
+/// How a command changes the browsing context that subsequent commands operate on.
+enum ContextChange {
+    /// Enter the child frame with the given description.
+    Enter(String),
+    /// Return to the parent of the current frame.
+    Parent,
+    /// Return to the top-level browsing context.
+    TopLevel,
+}
+
+impl ContextChange {
+    fn of(cmd: &Wcmd) -> Option<Self> {
+        use webdriver::common::FrameId;
+        match *cmd {
+            WebDriverCommand::SwitchToFrame(ref params) => Some(match params.id {
+                Some(FrameId::Short(i)) => ContextChange::Enter(format!("frame #{}", i)),
+                Some(FrameId::Element(ref e)) => {
+                    ContextChange::Enter(format!("frame element {}", e.0))
+                }
+                None => ContextChange::TopLevel,
+            }),
+            WebDriverCommand::SwitchToParentFrame => Some(ContextChange::Parent),
+            // https://www.w3.org/TR/webdriver1/#navigation
+            WebDriverCommand::Get(..)
+            | WebDriverCommand::GoBack
+            | WebDriverCommand::GoForward
+            | WebDriverCommand::Refresh
+            | WebDriverCommand::SwitchToWindow(..)
+            | WebDriverCommand::CloseWindow => Some(ContextChange::TopLevel),
+            _ => None,
+        }
     }
 }
 
@@ -91,6 +214,7 @@ enum Ongoing {
     WebDriver {
         ack: Ack,
         fut: Pin<Box<dyn Future<Output = Result<Json, error::CmdError>> + Send>>,
+        context: Option<ContextChange>,
     },
     Raw {
         ack: Ack,
@@ -102,7 +226,8 @@ enum Ongoing {
 enum OngoingResult {
     Continue,
     Break,
-    SessionId(String),
+    SessionId(String, Option<Json>),
+    ContextChange(ContextChange),
 }
 
 impl Ongoing {
@@ -126,14 +251,22 @@ impl Ongoing {
                 }
                 OngoingResult::Break
             }
-            Ongoing::WebDriver { mut fut, ack } => {
+            Ongoing::WebDriver {
+                mut fut,
+                ack,
+                context,
+            } => {
                 let rsp = if let Poll::Ready(v) = fut.as_mut().poll(cx) {
                     v
                 } else {
-                    *self = Ongoing::WebDriver { fut, ack };
+                    *self = Ongoing::WebDriver { fut, ack, context };
                     return Poll::Pending;
                 };
                 let mut rt = OngoingResult::Continue;
+                if let (Ok(_), Some(context)) = (&rsp, context) {
+                    // the command succeeded, so the context really did change
+                    rt = OngoingResult::ContextChange(context);
+                }
                 if try_extract_session {
                     // we can safely assume that this supposed to be a response to NewSession
                     // pick out the session id, because we'll need it later
@@ -143,7 +276,10 @@ impl Ongoing {
                         // and https://github.com/SeleniumHQ/selenium/blob/242d64ca4cd3523489ac1e58703fd7acd4f10c5a/py/selenium/webdriver/remote/webdriver.py#L200
                         if let Some(session_id) = v.get("sessionId") {
                             if let Some(session_id) = session_id.as_str() {
-                                rt = OngoingResult::SessionId(session_id.to_string());
+                                // legacy implementations return the capabilities as "value"
+                                let caps = v.get("capabilities").or_else(|| v.get("value"));
+                                rt =
+                                    OngoingResult::SessionId(session_id.to_string(), caps.cloned());
                             }
                         }
                     }
@@ -177,9 +313,16 @@ where
     client: hyper::Client<C>,
     wdb: url::Url,
     session: Option<String>,
+    /// The capabilities the WebDriver server reported when the current session was created.
+    capabilities: Option<Json>,
     is_legacy: bool,
     ua: Option<String>,
+    /// Credentials for HTTP basic authentication with the WebDriver server, if any.
+    basic_auth: Option<(String, String)>,
     persist: bool,
+    frames: Vec<String>,
+    /// The parameters the current WebDriver session was created with, for restarting it.
+    new_session: Option<webdriver::command::NewSessionParameters>,
 }
 
 impl<C> Future for Session<C>
@@ -194,8 +337,18 @@ where
                 let has_session = self.session.is_none();
                 match ready!(self.ongoing.poll(has_session, cx)) {
                     OngoingResult::Break => break,
-                    OngoingResult::SessionId(sid) => {
+                    OngoingResult::SessionId(sid, caps) => {
                         self.session = Some(sid);
+                        self.capabilities = caps;
+                    }
+                    OngoingResult::ContextChange(ContextChange::Enter(frame)) => {
+                        self.frames.push(frame);
+                    }
+                    OngoingResult::ContextChange(ContextChange::Parent) => {
+                        self.frames.pop();
+                    }
+                    OngoingResult::ContextChange(ContextChange::TopLevel) => {
+                        self.frames.clear();
                     }
                     OngoingResult::Continue => {}
                 }
@@ -213,6 +366,13 @@ where
                             .map(Json::String)
                             .unwrap_or(Json::Null)));
                     }
+                    Cmd::GetCapabilities => {
+                        let _ = ack.send(Ok(self.capabilities.clone().unwrap_or(Json::Null)));
+                    }
+                    Cmd::GetFramePath => {
+                        let path = self.frames.iter().cloned().map(Json::String).collect();
+                        let _ = ack.send(Ok(Json::Array(path)));
+                    }
                     Cmd::SetUa(ua) => {
                         self.ua = Some(ua);
                         let _ = ack.send(Ok(Json::Null));
@@ -236,6 +396,9 @@ where
                         // explicit client shutdown
                         self.shutdown(Some(ack));
                     }
+                    Cmd::Restart => {
+                        self.restart(ack);
+                    }
                     Cmd::WebDriver(request) => {
                         // looks like the client setup is falling back to legacy params
                         // keep track of that for later
@@ -245,8 +408,12 @@ where
                         {
                             self.is_legacy = true;
                         }
+                        if let WebDriverCommand::NewSession(ref params) = request {
+                            self.new_session = Some(clone_new_session(params));
+                        }
                         self.ongoing = Ongoing::WebDriver {
                             ack,
+                            context: ContextChange::of(&request),
                             fut: Box::pin(self.issue_wd_cmd(request)),
                         };
                     }
@@ -285,13 +452,79 @@ where
         self.ongoing = Ongoing::Shutdown {
             ack,
             fut: self.client.request(
-                hyper::Request::delete(url.as_str())
+                self.request(hyper::Method::DELETE, &url)
                     .body(hyper::Body::empty())
                     .unwrap(),
             ),
         };
     }
 
+    /// End the current WebDriver session, and start a new one with the same parameters.
+    fn restart(&mut self, ack: Ack) {
+        let params = match self.new_session {
+            Some(ref params) => clone_new_session(params),
+            None => {
+                let _ = ack.send(Err(error::CmdError::Lost(io::Error::new(
+                    io::ErrorKind::NotConnected,
+                    "no WebDriver session was ever started",
+                ))));
+                return;
+            }
+        };
+
+        // with no session id, the response to the new session will be picked up as usual
+        let delete = self.session.take().map(|session| {
+            let url = self.wdb.join(&format!("session/{}", session)).unwrap();
+            self.client.request(
+                self.request(hyper::Method::DELETE, &url)
+                    .body(hyper::Body::empty())
+                    .unwrap(),
+            )
+        });
+        self.frames.clear();
+        let create = self.issue_wd_cmd(WebDriverCommand::NewSession(params));
+
+        self.ongoing = Ongoing::WebDriver {
+            ack,
+            context: None,
+            fut: Box::pin(async move {
+                if let Some(delete) = delete {
+                    // the old session may well be broken, so don't let that stop us
+                    let _ = delete.await;
+                }
+                create.await
+            }),
+        };
+    }
+
+    /// Start building a request to the WebDriver server, with the headers every request needs.
+    fn request(&self, method: hyper::Method, url: &url::Url) -> http::request::Builder {
+        let mut req = hyper::Request::builder();
+        req = req.method(method).uri(url.as_str());
+        if let Some(ref s) = self.ua {
+            req = req.header(hyper::header::USER_AGENT, s.to_owned());
+        }
+        // because https://github.com/hyperium/hyper/pull/727
+        let credentials = if let Some((ref user, ref pass)) = self.basic_auth {
+            Some(format!("{}:{}", user, pass))
+        } else if !url.username().is_empty() || url.password().is_some() {
+            Some(format!(
+                "{}:{}",
+                url.username(),
+                url.password().unwrap_or("")
+            ))
+        } else {
+            None
+        };
+        if let Some(credentials) = credentials {
+            req = req.header(
+                hyper::header::AUTHORIZATION,
+                format!("Basic {}", base64::encode(credentials)),
+            );
+        }
+        req
+    }
+
     fn map_handshake_response(
         response: Result<Json, error::CmdError>,
     ) -> Result<(), error::NewSessionError> {
@@ -318,9 +551,7 @@ where
                 Err(error::NewSessionError::NotW3C(Json::String(v)))
             }
             Err(error::CmdError::Standard(
-                e
-                @
-                WebDriverError {
+                e @ WebDriverError {
                     error: ErrorStatus::SessionNotCreated,
                     ..
                 },
@@ -335,6 +566,7 @@ where
         webdriver: &str,
         cap: &webdriver::capabilities::Capabilities,
         connector: C,
+        basic_auth: Option<(String, String)>,
     ) -> Result<Client, error::NewSessionError> {
         // Where is the WebDriver server?
         let wdb = webdriver.parse::<url::Url>();
@@ -342,7 +574,7 @@ where
         // We want a tls-enabled client
         let client = hyper::Client::builder().build::<_, hyper::Body>(connector);
 
-        let mut cap = cap.to_owned();
+        let cap = session_capabilities(cap);
         // We're going to need a channel for sending requests to the WebDriver host
         let (tx, rx) = mpsc::unbounded_channel();
 
@@ -353,30 +585,26 @@ where
             client,
             wdb,
             session: None,
+            capabilities: None,
             is_legacy: false,
             ua: None,
+            basic_auth,
             persist: false,
+            frames: Vec::new(),
+            new_session: None,
         });
 
         // now that the session is running, let's do the handshake
         let mut client = Client {
             tx: tx.clone(),
             is_legacy: false,
+            wrap_scripts: false,
+            test_id_attribute: None,
+            helpers: None,
         };
 
         // Create a new session for this client
         // https://www.w3.org/TR/webdriver/#dfn-new-session
-        // https://www.w3.org/TR/webdriver/#capabilities
-        //  - we want the browser to wait for the page to load
-        cap.insert("pageLoadStrategy".to_string(), Json::from("normal"));
-
-        // make chrome comply with w3c
-        cap.entry("goog:chromeOptions".to_string())
-            .or_insert_with(|| Json::Object(serde_json::Map::new()))
-            .as_object_mut()
-            .expect("goog:chromeOptions wasn't a JSON object")
-            .insert("w3c".to_string(), Json::from(true));
-
         let session_config = webdriver::capabilities::SpecNewSessionParameters {
             alwaysMatch: cap.clone(),
             firstMatch: vec![webdriver::capabilities::Capabilities::new()],
@@ -391,6 +619,9 @@ where
             Ok(_) => Ok(Client {
                 tx,
                 is_legacy: false,
+                wrap_scripts: false,
+                test_id_attribute: None,
+                helpers: None,
             }),
             Err(error::NewSessionError::NotW3C(json)) => {
                 // maybe try legacy mode?
@@ -437,6 +668,9 @@ where
                 Ok(Client {
                     tx,
                     is_legacy: true,
+                    wrap_scripts: false,
+                    test_id_attribute: None,
+                    helpers: None,
                 })
             }
             Err(e) => Err(e),
@@ -446,25 +680,42 @@ where
     /// Helper for determining what URL endpoint to use for various requests.
     ///
     /// This mapping is essentially that of https://www.w3.org/TR/webdriver/#list-of-endpoints.
-    fn endpoint_for(&self, cmd: &Wcmd) -> Result<url::Url, url::ParseError> {
+    fn endpoint_for(&self, cmd: &Wcmd) -> Result<url::Url, error::CmdError> {
         if let WebDriverCommand::NewSession(..) = *cmd {
-            return self.wdb.join("session");
+            return Ok(self.wdb.join("session")?);
         }
 
-        let base = {
-            self.wdb
-                .join(&format!("session/{}/", self.session.as_ref().unwrap()))?
-        };
-        match *cmd {
+        let session = self.session.as_ref().ok_or_else(|| {
+            error::CmdError::Lost(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "there is no active WebDriver session",
+            ))
+        })?;
+        let base = { self.wdb.join(&format!("session/{}/", session))? };
+        let url = match *cmd {
             WebDriverCommand::NewSession(..) => unreachable!(),
             WebDriverCommand::DeleteSession => unreachable!(),
             WebDriverCommand::Get(..) | WebDriverCommand::GetCurrentUrl => base.join("url"),
             WebDriverCommand::GoBack => base.join("back"),
             WebDriverCommand::Refresh => base.join("refresh"),
+            WebDriverCommand::GetTitle => base.join("title"),
+            WebDriverCommand::GetTimeouts | WebDriverCommand::SetTimeouts(..) => {
+                base.join("timeouts")
+            }
             WebDriverCommand::GetPageSource => base.join("source"),
             WebDriverCommand::FindElement(..) => base.join("element"),
             WebDriverCommand::FindElements(..) => base.join("elements"),
-            WebDriverCommand::GetCookies => base.join("cookie"),
+            WebDriverCommand::GetCookies
+            | WebDriverCommand::AddCookie(..)
+            | WebDriverCommand::DeleteCookies => base.join("cookie"),
+            WebDriverCommand::GetNamedCookie(ref name)
+            | WebDriverCommand::DeleteCookie(ref name) => {
+                let mut url = base.join("cookie")?;
+                url.path_segments_mut()
+                    .expect("the session url is a base")
+                    .push(name);
+                Ok(url)
+            }
             WebDriverCommand::ExecuteScript(..) if self.is_legacy => base.join("execute"),
             WebDriverCommand::ExecuteScript(..) => base.join("execute/sync"),
             WebDriverCommand::ExecuteAsyncScript(..) => base.join("execute/async"),
@@ -474,6 +725,9 @@ where
             WebDriverCommand::GetElementAttribute(ref we, ref attr) => {
                 base.join(&format!("element/{}/attribute/{}", we.0, attr))
             }
+            WebDriverCommand::GetCSSValue(ref we, ref property) => {
+                base.join(&format!("element/{}/css/{}", we.0, property))
+            }
             WebDriverCommand::FindElementElement(ref p, _) => {
                 base.join(&format!("element/{}/element", p.0))
             }
@@ -485,11 +739,19 @@ where
             WebDriverCommand::GetElementText(ref we) => {
                 base.join(&format!("element/{}/text", we.0))
             }
+            WebDriverCommand::GetElementTagName(ref we) => {
+                base.join(&format!("element/{}/name", we.0))
+            }
+            WebDriverCommand::IsSelected(ref we) => {
+                base.join(&format!("element/{}/selected", we.0))
+            }
+            WebDriverCommand::IsEnabled(ref we) => base.join(&format!("element/{}/enabled", we.0)),
             WebDriverCommand::ElementSendKeys(ref we, _) => {
                 base.join(&format!("element/{}/value", we.0))
             }
             WebDriverCommand::SetWindowRect(..) => base.join("window/rect"),
             WebDriverCommand::GetWindowRect => base.join("window/rect"),
+            WebDriverCommand::MaximizeWindow => base.join("window/maximize"),
             WebDriverCommand::TakeScreenshot => base.join("screenshot"),
             WebDriverCommand::TakeElementScreenshot(ref we) => {
                 base.join(&format!("element/{}/screenshot", we.0))
@@ -502,8 +764,20 @@ where
             WebDriverCommand::SwitchToWindow(..) => base.join("window"),
             WebDriverCommand::CloseWindow => base.join("window"),
             WebDriverCommand::GetActiveElement => base.join("element/active"),
-            _ => unimplemented!(),
-        }
+            WebDriverCommand::PerformActions(..) | WebDriverCommand::ReleaseActions => {
+                base.join("actions")
+            }
+            WebDriverCommand::Extension(ExtensionCommand::ExecuteCdp { .. }) => {
+                base.join("goog/cdp/execute")
+            }
+            _ => {
+                return Err(error::CmdError::InvalidArgument(
+                    "cmd".to_string(),
+                    format!("{:?} is not supported", cmd),
+                ))
+            }
+        };
+        Ok(url?)
     }
 
     /// Helper for issuing a WebDriver command, and then reading and parsing the response.
@@ -513,19 +787,16 @@ where
     /// encoded arguments (if any) into the body.
     ///
     /// [the spec]: https://www.w3.org/TR/webdriver/#list-of-endpoints
-    fn issue_wd_cmd(
-        &mut self,
-        cmd: WebDriverCommand<webdriver::command::VoidWebDriverExtensionCommand>,
-    ) -> impl Future<Output = Result<Json, error::CmdError>> {
+    fn issue_wd_cmd(&mut self, cmd: Wcmd) -> impl Future<Output = Result<Json, error::CmdError>> {
         // TODO: make this an async fn
         // will take some doing as returned future must be independent of self
 
-        use webdriver::command;
+        use webdriver::command::{self, WebDriverExtensionCommand};
 
         // most actions are just get requests with not parameters
         let url = match self.endpoint_for(&cmd) {
             Ok(url) => url,
-            Err(e) => return Either::Right(future::err(error::CmdError::from(e))),
+            Err(e) => return Either::Right(future::err(e)),
         };
         use hyper::Method;
         let mut method = Method::GET;
@@ -586,7 +857,8 @@ where
             WebDriverCommand::ElementClick(..)
             | WebDriverCommand::ElementClear(..)
             | WebDriverCommand::GoBack
-            | WebDriverCommand::Refresh => {
+            | WebDriverCommand::Refresh
+            | WebDriverCommand::MaximizeWindow => {
                 body = Some("{}".to_string());
                 method = Method::POST;
             }
@@ -610,32 +882,35 @@ where
                 body = Some(serde_json::to_string(params).unwrap());
                 method = Method::POST;
             }
-            WebDriverCommand::CloseWindow => {
+            WebDriverCommand::AddCookie(ref params) => {
+                let cookie = AddCookieParameters::serialize(params, serde_json::value::Serializer)
+                    .expect("cookies can be serialized");
+                body = Some(serde_json::json!({ "cookie": cookie }).to_string());
+                method = Method::POST;
+            }
+            WebDriverCommand::CloseWindow
+            | WebDriverCommand::ReleaseActions
+            | WebDriverCommand::DeleteCookies
+            | WebDriverCommand::DeleteCookie(..) => {
                 method = Method::DELETE;
             }
+            WebDriverCommand::SetTimeouts(ref params) => {
+                body = Some(serde_json::to_string(params).unwrap());
+                method = Method::POST;
+            }
+            WebDriverCommand::PerformActions(ref params) => {
+                body = Some(serde_json::to_string(params).unwrap());
+                method = Method::POST;
+            }
+            WebDriverCommand::Extension(ref ext) => {
+                body = ext.parameters_json().map(|params| params.to_string());
+                method = Method::POST;
+            }
             _ => {}
         }
 
         // issue the command to the webdriver server
-        let mut req = hyper::Request::builder();
-        req = req.method(method).uri(url.as_str());
-        if let Some(ref s) = self.ua {
-            req = req.header(hyper::header::USER_AGENT, s.to_owned());
-        }
-        // because https://github.com/hyperium/hyper/pull/727
-        if !url.username().is_empty() || url.password().is_some() {
-            req = req.header(
-                hyper::header::AUTHORIZATION,
-                format!(
-                    "Basic {}",
-                    base64::encode(&format!(
-                        "{}:{}",
-                        url.username(),
-                        url.password().unwrap_or("")
-                    ))
-                ),
-            );
-        }
+        let mut req = self.request(method, &url);
 
         let req = if let Some(body) = body.take() {
             req = req.header(hyper::header::CONTENT_TYPE, mime::APPLICATION_JSON.as_ref());
@@ -693,7 +968,7 @@ where
 
                 // https://www.w3.org/TR/webdriver/#dfn-send-a-response
                 // NOTE: the standard specifies that even errors use the "Send a Reponse" steps
-                let body = match serde_json::from_str(&*body)? {
+                let body = match serde_json::from_str(&body)? {
                     Json::Object(mut v) => {
                         if legacy {
                             legacy_status = v["status"].as_u64().unwrap();
@@ -833,7 +1108,11 @@ where
                 };
 
                 let message = body["message"].as_str().unwrap().to_string();
-                Err(error::CmdError::from(WebDriverError::new(es, message)))
+                let mut e = WebDriverError::new(es, message);
+                if let Some(stack) = body.get("stacktrace").and_then(|s| s.as_str()) {
+                    e.stack = stack.to_string().into();
+                }
+                Err(error::CmdError::from(e))
             });
 
         Either::Left(f)
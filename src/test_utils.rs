@@ -0,0 +1,95 @@
+//! Conveniences for writing tests with fantoccini.
+//!
+//! When a browser test fails, the error alone rarely says why, and by the time anyone looks, the
+//! browser is gone. The extension methods here save what the page looked like at the moment of
+//! failure, so that it can be inspected afterwards:
+//!
+//! ```no_run
+//! # use fantoccini::{Client, Locator};
+//! use fantoccini::test_utils::ClientResultExt;
+//!
+//! # async fn example(mut c: Client) -> Result<(), fantoccini::error::CmdError> {
+//! let button = c
+//!     .find(Locator::Css("#submit"))
+//!     .await
+//!     .or_screenshot(&mut c, "submit-missing.png")
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! This module is only available with the `test-utils` feature.
+
+use crate::error::CmdError;
+use crate::Client;
+use futures_util::future::BoxFuture;
+use std::path::Path;
+
+/// Extension methods for the results of [`Client`] operations, that record the state of the page
+/// when the operation failed.
+///
+/// Recording the page is best-effort: if it fails too, the original error is still returned as
+/// is, since that is the error that matters.
+pub trait ClientResultExt<T> {
+    /// If this is an error, save a PNG screenshot of the current page to `path` before returning
+    /// the error.
+    fn or_screenshot<'a, P>(
+        self,
+        client: &'a mut Client,
+        path: P,
+    ) -> BoxFuture<'a, Result<T, CmdError>>
+    where
+        P: AsRef<Path> + Send + 'a,
+        T: 'a;
+
+    /// If this is an error, save the HTML source of the current page to `path` before returning
+    /// the error.
+    fn or_page_source<'a, P>(
+        self,
+        client: &'a mut Client,
+        path: P,
+    ) -> BoxFuture<'a, Result<T, CmdError>>
+    where
+        P: AsRef<Path> + Send + 'a,
+        T: 'a;
+}
+
+impl<T: Send> ClientResultExt<T> for Result<T, CmdError> {
+    fn or_screenshot<'a, P>(
+        self,
+        client: &'a mut Client,
+        path: P,
+    ) -> BoxFuture<'a, Result<T, CmdError>>
+    where
+        P: AsRef<Path> + Send + 'a,
+        T: 'a,
+    {
+        Box::pin(async move {
+            if self.is_err() {
+                if let Ok(png) = client.screenshot().await {
+                    let _ = std::fs::write(path, png);
+                }
+            }
+            self
+        })
+    }
+
+    fn or_page_source<'a, P>(
+        self,
+        client: &'a mut Client,
+        path: P,
+    ) -> BoxFuture<'a, Result<T, CmdError>>
+    where
+        P: AsRef<Path> + Send + 'a,
+        T: 'a,
+    {
+        Box::pin(async move {
+            if self.is_err() {
+                if let Ok(html) = client.source().await {
+                    let _ = std::fs::write(path, html);
+                }
+            }
+            self
+        })
+    }
+}
@@ -5,6 +5,7 @@ extern crate futures_util;
 
 use fantoccini::{error, Client, ClientBuilder};
 
+use hyper::client::connect;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Response, Server, StatusCode};
 use serde_json::map;
@@ -79,6 +80,21 @@ pub async fn make_client(
     }
 }
 
+pub async fn make_configured_client<C, F>(
+    url: &str,
+    caps: map::Map<String, serde_json::Value>,
+    mut builder: ClientBuilder<C>,
+    configure: F,
+) -> Result<Client, error::NewSessionError>
+where
+    C: connect::Connect + Send + Sync + Clone + Unpin + 'static,
+    F: FnOnce(&mut ClientBuilder<C>),
+{
+    builder.capabilities(caps);
+    configure(&mut builder);
+    builder.connect(url).await
+}
+
 pub fn make_url(s: &str) -> &'static str {
     match s {
         "firefox" => "http://localhost:4444",
@@ -172,6 +188,36 @@ macro_rules! local_tester {
     }};
 }
 
+#[macro_export]
+macro_rules! local_tester_with {
+    ($f:ident, $endpoint:expr, $configure:expr) => {{
+        let port = common::setup_server();
+        let url = common::make_url($endpoint);
+        let caps = common::make_capabilities($endpoint);
+        let f = move |c: Client| async move { $f(c, port).await };
+        #[cfg(feature = "rustls-tls")]
+        tester_inner!(
+            f,
+            common::make_configured_client(
+                url,
+                caps.clone(),
+                fantoccini::ClientBuilder::rustls(),
+                $configure
+            )
+        );
+        #[cfg(feature = "native-tls")]
+        tester_inner!(
+            f,
+            common::make_configured_client(
+                url,
+                caps,
+                fantoccini::ClientBuilder::native(),
+                $configure
+            )
+        )
+    }};
+}
+
 /// Sets up the server and returns the port it bound to.
 pub fn setup_server() -> u16 {
     let (tx, rx) = std::sync::mpsc::channel();
@@ -181,7 +227,7 @@ pub fn setup_server() -> u16 {
             .enable_all()
             .build()
             .unwrap();
-        let _ = rt.block_on(async {
+        rt.block_on(async {
             let (socket_addr, server) = start_server();
             tx.send(socket_addr.port())
                 .expect("To be able to send port");
@@ -211,12 +257,37 @@ fn start_server() -> (
 async fn handle_file_request(req: Request<Body>) -> Result<Response<Body>, Infallible> {
     let uri_path = req.uri().path().trim_matches(&['/', '\\'][..]);
 
-    // tests only contain html files
-    // needed because the content-type: text/html is returned
-    if !uri_path.ends_with(".html") {
+    // files under protected/ require HTTP basic authentication as user:pass
+    let uri_path = match uri_path.strip_prefix("protected/") {
+        Some(path) => {
+            let auth = req.headers().get(hyper::header::AUTHORIZATION);
+            if auth.map(|v| v.as_bytes()) != Some(b"Basic dXNlcjpwYXNz") {
+                return Ok(Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .header(hyper::header::WWW_AUTHENTICATE, "Basic realm=\"tests\"")
+                    .body(Body::empty())
+                    .unwrap());
+            }
+            path
+        }
+        None => uri_path,
+    };
+
+    // requests for anything under hang/ never finish (as far as tests are concerned)
+    if uri_path.starts_with("hang/") {
+        tokio::time::sleep(std::time::Duration::from_secs(300)).await;
         return Ok(file_not_found());
     }
 
+    // tests only contain html files, and the scripts they load
+    let content_type = if uri_path.ends_with(".html") {
+        "text/html"
+    } else if uri_path.ends_with(".js") {
+        "application/javascript"
+    } else {
+        return Ok(file_not_found());
+    };
+
     // this does not protect against a directory traversal attack
     // but in this case it's not a risk
     let asset_file = Path::new(ASSETS_DIR).join(uri_path);
@@ -226,11 +297,14 @@ async fn handle_file_request(req: Request<Body>) -> Result<Response<Body>, Infal
         Err(_) => return Ok(file_not_found()),
     };
 
-    let res = Response::builder()
-        .header("content-type", "text/html")
-        .header("content-length", ctn.len())
-        .body(ctn.into())
-        .unwrap();
+    let mut res = Response::builder()
+        .header("content-type", content_type)
+        .header("content-length", ctn.len());
+    // files requested with ?cache may be served from the browser's cache
+    if req.uri().query() == Some("cache") {
+        res = res.header("cache-control", "max-age=3600");
+    }
+    let res = res.body(ctn.into()).unwrap();
 
     Ok(res)
 }
@@ -4,7 +4,11 @@ extern crate serial_test;
 extern crate fantoccini;
 extern crate futures_util;
 
-use fantoccini::{error, Client, Locator};
+use fantoccini::{
+    error, Browser, Client, ImageFormat, Locator, ScrapeField, ScreenshotOptions, Timeouts,
+};
+use std::time::Duration;
+use webdriver::common::WebWindow;
 
 mod common;
 
@@ -12,6 +16,12 @@ fn sample_page_url(port: u16) -> String {
     format!("http://localhost:{}/sample_page.html", port)
 }
 
+/// Get the width and height of a PNG, which are the first two fields of its header.
+fn png_size(png: &[u8]) -> (u32, u32) {
+    let field = |at: usize| u32::from_be_bytes([png[at], png[at + 1], png[at + 2], png[at + 3]]);
+    (field(16), field(20))
+}
+
 async fn goto(mut c: Client, port: u16) -> Result<(), error::CmdError> {
     let url = sample_page_url(port);
     c.goto(&url).await?;
@@ -310,7 +320,7 @@ async fn resolve_execute_async_value(mut c: Client, port: u16) -> Result<(), err
 
     let count: u64 = c
         .execute_async(
-            &"setTimeout(() => arguments[1](arguments[0] + 1))",
+            "setTimeout(() => arguments[1](arguments[0] + 1))",
             vec![1_u32.into()],
         )
         .await?
@@ -320,7 +330,7 @@ async fn resolve_execute_async_value(mut c: Client, port: u16) -> Result<(), err
     assert_eq!(2, count);
 
     let count: u64 = c
-        .execute_async(&"setTimeout(() => arguments[0](2))", vec![])
+        .execute_async("setTimeout(() => arguments[0](2))", vec![])
         .await?
         .as_u64()
         .expect("should be integer variant");
@@ -330,164 +340,3322 @@ async fn resolve_execute_async_value(mut c: Client, port: u16) -> Result<(), err
     Ok(())
 }
 
-mod firefox {
-    use super::*;
-    #[test]
-    #[serial]
-    fn navigate_to_other_page() {
-        local_tester!(goto, "firefox");
-    }
+async fn wrapped_scripts_do_not_leak(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = sample_page_url(port);
+    c.goto(&url).await?;
 
-    #[test]
-    #[serial]
-    fn find_and_click_link_test() {
-        local_tester!(find_and_click_link, "firefox");
+    // without wrapping, the second declaration may clash with the first
+    for _ in 0..2 {
+        let x = c.execute("const x = 1; return x;", vec![]).await?;
+        assert_eq!(x.as_u64(), Some(1));
     }
 
-    #[test]
-    #[serial]
-    fn get_active_element_test() {
-        local_tester!(get_active_element, "firefox");
-    }
+    let sum = c
+        .execute(
+            "return arguments[0] + arguments[1];",
+            vec![1.into(), 2.into()],
+        )
+        .await?;
+    assert_eq!(sum.as_u64(), Some(3));
 
-    #[test]
-    #[serial]
-    fn serialize_element_test() {
-        local_tester!(serialize_element, "firefox");
-    }
+    Ok(())
+}
 
-    #[test]
-    #[serial]
-    fn iframe_test() {
-        local_tester!(iframe_switch, "firefox");
-    }
+async fn windows_info(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    let original = c.window().await?;
 
-    #[test]
-    #[serial]
-    fn new_window_test() {
-        tester!(new_window, "firefox");
-    }
+    let handle = c.new_window(true).await?.handle;
+    c.switch_to_window(WebWindow(handle.clone())).await?;
+    c.goto(&format!("http://localhost:{}/other_page.html", port))
+        .await?;
+    c.switch_to_window(original.clone()).await?;
+
+    let infos = c.windows_info().await?;
+    assert_eq!(infos.len(), 2);
+    let sample = infos
+        .iter()
+        .find(|w| w.title == "Sample Page")
+        .expect("should find the sample page by title");
+    assert_eq!(sample.handle, original);
+    let other = infos
+        .iter()
+        .find(|w| w.url.path() == "/other_page.html")
+        .expect("should find the other page by url");
+    assert_eq!(other.handle, WebWindow(handle));
+
+    // we should be back where we started
+    assert_eq!(c.window().await?, original);
 
-    #[test]
-    #[serial]
-    fn new_window_switch_test() {
-        tester!(new_window_switch, "firefox");
-    }
+    Ok(())
+}
 
-    #[test]
-    #[serial]
-    fn new_tab_switch_test() {
-        tester!(new_tab_switch, "firefox");
-    }
+async fn wait_for_network_idle(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
 
-    #[test]
-    #[serial]
-    fn close_window_test() {
-        tester!(close_window, "firefox");
-    }
+    // install the tracking before the request is made
+    c.wait_for_network_idle(Duration::from_millis(0), Duration::from_secs(5))
+        .await?;
+    c.execute(
+        "fetch('other_page.html').then(r => r.text()).then(() => { window.fetched = true; });",
+        vec![],
+    )
+    .await?;
+    c.wait_for_network_idle(Duration::from_millis(500), Duration::from_secs(5))
+        .await?;
+    assert_eq!(
+        c.execute("return window.fetched === true;", vec![]).await?,
+        serde_json::Value::Bool(true)
+    );
 
-    #[test]
-    #[serial]
-    fn double_close_window_test() {
-        tester!(close_window_twice_errors, "firefox");
-    }
+    c.stop_tracking_network().await?;
+    assert_eq!(
+        c.execute("return window.__fantoccini_network === undefined;", vec![])
+            .await?,
+        serde_json::Value::Bool(true)
+    );
 
-    #[test]
-    #[serial]
-    fn set_by_name_textarea_test() {
-        local_tester!(set_by_name_textarea, "firefox");
-    }
+    Ok(())
+}
 
-    #[test]
-    #[serial]
-    fn stale_element_test() {
-        local_tester!(stale_element, "firefox");
-    }
+async fn clipboard(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/clipboard_page.html", port);
+    c.goto(&url).await?;
 
-    #[test]
-    #[serial]
-    fn select_by_index_test() {
-        local_tester!(select_by_index, "firefox");
-    }
+    c.write_clipboard("some text").await?;
+    assert_eq!(c.read_clipboard().await?, "some text");
 
-    #[test]
-    #[serial]
-    fn select_by_label_test() {
-        local_tester!(select_by_label, "firefox");
-    }
+    c.find(Locator::Id("copy_button")).await?.click().await?;
+    assert_eq!(c.read_clipboard().await?, "https://example.com/shared");
 
-    #[test]
-    #[serial]
-    fn resolve_execute_async_value_test() {
-        local_tester!(resolve_execute_async_value, "firefox");
-    }
+    Ok(())
 }
 
-mod chrome {
-    use super::*;
-    #[test]
-    fn navigate_to_other_page() {
-        local_tester!(goto, "chrome");
-    }
+async fn emulate_media(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/media_page.html", port);
+    c.goto(&url).await?;
 
-    #[test]
-    fn find_and_click_link_test() {
-        local_tester!(find_and_click_link, "chrome");
-    }
+    let background = "return getComputedStyle(document.body).backgroundColor;";
+    c.emulate_media(&[("prefers-color-scheme".to_string(), "light".to_string())])
+        .await?;
+    assert_eq!(c.execute(background, vec![]).await?, "rgb(255, 255, 255)");
 
-    #[test]
-    fn get_active_element_test() {
-        local_tester!(get_active_element, "chrome");
-    }
+    c.emulate_media(&[("prefers-color-scheme".to_string(), "dark".to_string())])
+        .await?;
+    assert_eq!(c.execute(background, vec![]).await?, "rgb(0, 0, 0)");
 
-    #[test]
-    fn serialize_element_test() {
-        local_tester!(serialize_element, "chrome");
-    }
+    c.clear_emulated_media().await?;
+    Ok(())
+}
 
-    #[test]
-    fn iframe_test() {
-        local_tester!(iframe_switch, "chrome");
+async fn javascript_error(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+
+    match c.execute("throw new Error('boom');", vec![]).await {
+        Err(error::CmdError::JavascriptError {
+            message,
+            stacktrace,
+        }) => {
+            assert!(message.contains("boom"), "unexpected message: {}", message);
+            assert!(!stacktrace.is_empty());
+        }
+        r => panic!("expected a javascript error, got {:?}", r),
     }
 
-    #[test]
-    fn new_window_test() {
-        tester!(new_window, "chrome");
-    }
+    Ok(())
+}
 
-    #[test]
-    fn new_window_switch_test() {
-        tester!(new_window_switch, "chrome");
-    }
+async fn scroll_to_bottom(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/infinite_scroll.html", port);
+    c.goto(&url).await?;
 
-    #[test]
-    fn new_tab_test() {
-        tester!(new_tab_switch, "chrome");
+    // three scrolls load more content, and the fourth finds nothing new
+    let steps = c.scroll_to_bottom(10, Duration::from_millis(500)).await?;
+    assert_eq!(steps, 4);
+    assert_eq!(c.find_all(Locator::Css(".item")).await?.len(), 80);
+
+    // the limit is respected
+    c.refresh().await?;
+    let steps = c.scroll_to_bottom(1, Duration::from_millis(500)).await?;
+    assert_eq!(steps, 1);
+    assert_eq!(c.find_all(Locator::Css(".item")).await?.len(), 40);
+
+    Ok(())
+}
+
+async fn frame_depth(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/nested_iframe.html", port);
+    c.goto(&url).await?;
+    assert_eq!(c.frame_depth().await?, 0);
+    assert_eq!(
+        c.current_context_description().await?,
+        "top-level browsing context"
+    );
+
+    let mut c = c.enter_frame(Some(0)).await?;
+    let mut c = c.find(Locator::Id("iframe")).await?.enter_frame().await?;
+    c.find(Locator::Id("iframe_button")).await?;
+    assert_eq!(c.frame_depth().await?, 2);
+    let desc = c.current_context_description().await?;
+    assert!(desc.starts_with("top-level browsing context > frame #0 > frame element "));
+
+    // a failed switch does not change the context
+    c.clone()
+        .enter_frame(Some(5))
+        .await
+        .expect_err("there should be no such frame");
+    assert_eq!(c.frame_depth().await?, 2);
+
+    let mut c = c.enter_parent_frame().await?;
+    assert_eq!(c.frame_depth().await?, 1);
+
+    c.refresh().await?;
+    assert_eq!(c.frame_depth().await?, 0);
+
+    Ok(())
+}
+
+async fn hover(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/hover_menu.html", port);
+    c.goto(&url).await?;
+
+    let visible =
+        r#"return getComputedStyle(document.getElementById("submenu")).display !== "none";"#;
+    assert_eq!(c.execute(visible, vec![]).await?.as_bool(), Some(false));
+    c.find(Locator::Id("menu")).await?.hover().await?;
+    assert_eq!(c.execute(visible, vec![]).await?.as_bool(), Some(true));
+    assert_eq!(
+        c.find(Locator::Id("entered")).await?.text().await?,
+        "entered"
+    );
+
+    // an element that is never shown cannot be hovered
+    let mut item = c.find(Locator::Id("hidden")).await?;
+    match item.hover().await {
+        Err(error::CmdError::ElementNotInteractable(_)) => {}
+        r => panic!("hovering a hidden element should fail, got {:?}", r),
     }
 
-    #[test]
-    fn close_window_test() {
-        tester!(close_window, "chrome");
+    Ok(())
+}
+
+async fn location_in_page(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/infinite_scroll.html", port);
+    c.goto(&url).await?;
+
+    let viewport_top = "return arguments[0].getBoundingClientRect().top;";
+    let mut e = c.find(Locator::Css("#feed > :nth-child(15)")).await?;
+    let before = e.location_in_page().await?;
+    let args = vec![serde_json::to_value(&e)?];
+    let viewport_before = c.execute(viewport_top, args.clone()).await?.as_f64();
+
+    c.execute("window.scrollTo(0, 300);", vec![]).await?;
+    assert_eq!(e.location_in_page().await?, before);
+    let viewport_after = c.execute(viewport_top, args).await?.as_f64();
+    assert_ne!(viewport_before, viewport_after);
+
+    Ok(())
+}
+
+async fn find_by_test_id(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/test_ids.html", port);
+    c.goto(&url).await?;
+
+    let mut e = c.find(Locator::TestId("submit")).await?;
+    assert_eq!(e.text().await?, "Submit");
+    let mut e = c.find(Locator::TestId(r#"say "hi" \ bye"#)).await?;
+    assert_eq!(e.text().await?, "Quoted");
+    // the default data-testid attribute is not used once another one is configured
+    assert!(c
+        .find(Locator::TestId("default"))
+        .await
+        .unwrap_err()
+        .is_miss());
+
+    Ok(())
+}
+
+async fn wait_for_gone(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/spinner.html", port);
+    c.goto(&url).await?;
+
+    // nothing is going away, so these should time out
+    match c
+        .wait_for_not_present(Locator::Id("load"), Duration::from_millis(200))
+        .await
+    {
+        Err(error::CmdError::WaitTimeout) => {}
+        r => panic!("expected a timeout, got {:?}", r),
+    }
+    match c
+        .wait_for_not_displayed(Locator::Id("load"), Duration::from_millis(200))
+        .await
+    {
+        Err(error::CmdError::WaitTimeout) => {}
+        r => panic!("expected a timeout, got {:?}", r),
     }
 
-    #[test]
-    fn double_close_window_test() {
-        tester!(close_window_twice_errors, "chrome");
+    // the spinner is hidden and the toast removed a little while after the click
+    let mut c = c.find(Locator::Id("load")).await?.click().await?;
+    c.find(Locator::Id("spinner")).await?;
+    c.wait_for_not_displayed(Locator::Id("spinner"), Duration::from_secs(5))
+        .await?;
+    c.wait_for_not_present(Locator::Id("toast"), Duration::from_secs(5))
+        .await?;
+    // the spinner is hidden, not removed
+    c.find(Locator::Id("spinner")).await?;
+
+    Ok(())
+}
+
+async fn screenshot_surface(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/infinite_scroll.html", port);
+    c.goto(&url).await?;
+
+    // PNG images start with an 8 byte signature, followed by the IHDR chunk, which holds the
+    // width and height of the image as big-endian 32-bit integers
+    fn png_height(png: &[u8]) -> u64 {
+        assert_eq!(&png[1..4], b"PNG");
+        u64::from(u32::from_be_bytes([png[20], png[21], png[22], png[23]]))
     }
 
-    #[test]
-    fn set_by_name_textarea_test() {
-        local_tester!(set_by_name_textarea, "chrome");
+    let viewport = c.execute("return window.innerHeight;", vec![]).await?;
+    let viewport = viewport.as_u64().unwrap();
+
+    let png = c.screenshot_surface(&ScreenshotOptions::default()).await?;
+    assert!(png_height(&png) <= viewport);
+
+    let options = ScreenshotOptions {
+        full_page: true,
+        ..Default::default()
+    };
+    let png = c.screenshot_surface(&options).await?;
+    assert!(png_height(&png) > viewport);
+
+    let options = ScreenshotOptions {
+        format: ImageFormat::Jpeg,
+        quality: Some(50),
+        clip: Some((0.0, 0.0, 100.0, 50.0)),
+        ..Default::default()
+    };
+    let jpeg = c.screenshot_surface(&options).await?;
+    assert_eq!(&jpeg[..2], &[0xFF, 0xD8]);
+
+    Ok(())
+}
+
+async fn wait_for_attribute(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/hover_menu.html", port);
+    c.goto(&url).await?;
+
+    let mut menu = c.find(Locator::Id("menu")).await?;
+    let expanded = |v: Option<&str>| v == Some("true");
+    match menu
+        .wait_for_attribute("aria-expanded", expanded, Duration::from_millis(200))
+        .await
+    {
+        Err(error::CmdError::WaitTimeout) => {}
+        r => panic!("expected a timeout, got {:?}", r),
     }
 
-    #[test]
-    #[serial]
-    fn select_by_label_test() {
-        local_tester!(select_by_label, "chrome");
+    // the menu is marked as expanded a little while after the pointer enters it
+    menu.hover().await?;
+    let value = menu
+        .wait_for_attribute("aria-expanded", expanded, Duration::from_secs(5))
+        .await?;
+    assert_eq!(value.as_deref(), Some("true"));
+
+    Ok(())
+}
+
+async fn issue_nowait(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use webdriver::command::{JavascriptCommandParameters, WebDriverCommand};
+
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    let script = |script: &str| {
+        WebDriverCommand::ExecuteScript(JavascriptCommandParameters {
+            script: script.to_string(),
+            args: Some(vec![]),
+        })
+    };
+    c.issue_nowait(script("window.fantocciniLog = [];"))?;
+    for i in 0..10 {
+        c.issue_nowait(script(&format!("window.fantocciniLog.push({});", i)))?;
     }
+    // a failing command does not stop the ones after it
+    c.issue_nowait(script("throw new Error('ignored');"))?;
+    c.issue_nowait(script("window.fantocciniLog.push('done');"))?;
 
-    #[test]
-    fn select_by_index_label() {
-        local_tester!(select_by_index, "chrome");
+    // this is only sent once all of the above have completed
+    let log = c.execute("return window.fantocciniLog;", vec![]).await?;
+    assert_eq!(
+        log,
+        serde_json::json!([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, "done"])
+    );
+
+    Ok(())
+}
+
+async fn mock_geolocation(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    c.mock_geolocation(59.91, 10.75, 10.0).await?;
+    let position = c
+        .execute_async(
+            r#"const [done] = arguments;
+            navigator.geolocation.getCurrentPosition(
+                (pos) => done([pos.coords.latitude, pos.coords.longitude, pos.coords.accuracy]),
+                (err) => done(err.message),
+            );"#,
+            vec![],
+        )
+        .await?;
+    assert_eq!(position, serde_json::json!([59.91, 10.75, 10.0]));
+
+    Ok(())
+}
+
+async fn scrape(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/table.html", port);
+    c.goto(&url).await?;
+
+    let rows = c
+        .scrape(
+            Locator::Css("#people tbody tr"),
+            &[
+                ScrapeField::Attribute("data-name"),
+                ScrapeField::Attribute("data-missing"),
+                ScrapeField::Property("rowIndex"),
+            ],
+        )
+        .await?;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0]["data-name"], "Alice");
+    assert_eq!(rows[0]["rowIndex"], "1");
+    assert_eq!(rows[2]["data-name"], "Carol");
+    assert_eq!(rows[2]["rowIndex"], "3");
+    assert!(rows.iter().all(|row| !row.contains_key("data-missing")));
+
+    let links = c
+        .scrape(
+            Locator::XPath("//td/a"),
+            &[ScrapeField::Text, ScrapeField::Attribute("href")],
+        )
+        .await?;
+    assert_eq!(links.len(), 3);
+    assert_eq!(links[1]["text"], "Bob");
+    assert_eq!(links[1]["href"], "/people/bob.html");
+
+    let none = c
+        .scrape(Locator::Css(".nothing"), &[ScrapeField::Text])
+        .await?;
+    assert!(none.is_empty());
+
+    Ok(())
+}
+
+async fn with_timeouts(mut c: Client, _: u16) -> Result<(), error::CmdError> {
+    let implicit = |ms| Timeouts {
+        implicit: Some(Duration::from_millis(ms)),
+        ..Default::default()
+    };
+    c.set_timeouts(implicit(100)).await?;
+    let before = c.get_timeouts().await?;
+    assert_eq!(before.implicit, Some(Duration::from_millis(100)));
+
+    let inner = c
+        .with_timeouts(
+            implicit(2000),
+            |mut c| async move { c.get_timeouts().await },
+        )
+        .await?;
+    assert_eq!(inner.implicit, Some(Duration::from_millis(2000)));
+    // the other timeouts are left alone
+    assert_eq!(inner.page_load, before.page_load);
+    assert_eq!(inner.script, before.script);
+    assert_eq!(c.get_timeouts().await?, before);
+
+    // the timeouts are restored even if the closure fails
+    let res = c
+        .with_timeouts(implicit(2000), |mut c| async move {
+            c.find(Locator::Id("does-not-exist")).await
+        })
+        .await;
+    assert!(res.unwrap_err().is_miss());
+    assert_eq!(c.get_timeouts().await?, before);
+
+    Ok(())
+}
+
+async fn execute_in_frame(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/iframe_outer.html", port);
+    c.goto(&url).await?;
+
+    let title = c
+        .execute_in_frame(Locator::Id("iframe"), "return document.title;", vec![])
+        .await?;
+    assert_eq!(title, "Iframe Inner");
+    assert_eq!(c.frame_depth().await?, 0);
+    assert_eq!(c.title().await?, "Iframe Container");
+
+    // the context is restored even if the script fails
+    let res = c
+        .execute_in_frame(Locator::Id("iframe"), "throw new Error('boom');", vec![])
+        .await;
+    assert!(matches!(res, Err(error::CmdError::JavascriptError { .. })));
+    assert_eq!(c.frame_depth().await?, 0);
+    c.find(Locator::Id("iframe")).await?;
+
+    Ok(())
+}
+
+async fn set_cookie_cdp(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::cookies::Cookie;
+
+    // set the cookies before ever visiting the site
+    let mut visible = Cookie::new("visible", "yes");
+    visible.set_domain("localhost");
+    c.set_cookie_cdp(&visible).await?;
+    let mut hidden = Cookie::new("hidden", "yes");
+    hidden.set_domain("localhost").set_http_only(true);
+    c.set_cookie_cdp(&hidden).await?;
+
+    let no_domain = Cookie::new("nowhere", "yes");
+    match c.set_cookie_cdp(&no_domain).await {
+        Err(error::CmdError::InvalidArgument(..)) => {}
+        r => panic!("a cookie without a domain should be rejected, got {:?}", r),
+    }
+
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    let cookies = c.execute("return document.cookie;", vec![]).await?;
+    let cookies = cookies.as_str().unwrap();
+    assert!(cookies.contains("visible=yes"));
+    assert!(!cookies.contains("hidden"));
+
+    Ok(())
+}
+
+async fn restart(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    let mut other = c.clone();
+    let old = c.session_id().await?;
+    let mut elem = c.find(Locator::Id("other_page_id")).await?;
+
+    c.restart().await?;
+    assert_ne!(c.session_id().await?, old);
+    // clones move over to the new session too
+    assert_eq!(other.session_id().await?, c.session_id().await?);
+    assert_eq!(other.current_url().await?.as_str(), "about:blank");
+    // but references into the old session are gone
+    assert!(elem.attr("id").await.is_err());
+
+    Ok(())
+}
+
+async fn readable_text(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/article.html", port);
+    c.goto(&url).await?;
+
+    let text = c.readable_text().await?;
+    assert_eq!(
+        text,
+        "Main Headline\n\
+         The first paragraph, with some emphasis.\n\
+         Buy now!\n\
+         First point\n\
+         Second point"
+    );
+    for boilerplate in &[
+        "Site Banner",
+        "Home",
+        "Related links",
+        "Copyright",
+        "script text",
+    ] {
+        assert!(
+            !text.contains(boilerplate),
+            "{:?} in {:?}",
+            boilerplate,
+            text
+        );
+    }
+
+    let text = c.readable_text_excluding(&[".advert"]).await?;
+    assert!(!text.contains("Buy now!"));
+    assert!(text.contains("First point"));
+
+    Ok(())
+}
+
+async fn count(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/article.html", port);
+    c.goto(&url).await?;
+
+    assert_eq!(c.count(Locator::Css("li")).await?, 2);
+    assert_eq!(c.count(Locator::Css("main p")).await?, 2);
+    assert_eq!(c.count(Locator::Css("table")).await?, 0);
+    // non-CSS locators are counted too
+    assert_eq!(c.count(Locator::XPath("//nav/a")).await?, 2);
+    assert_eq!(
+        c.count(Locator::XPath("//li")).await?,
+        c.find_all(Locator::Css("li")).await?.len()
+    );
+
+    Ok(())
+}
+
+async fn set_viewport(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    c.set_viewport(375, 667).await?;
+    let size = c
+        .execute("return [window.innerWidth, window.innerHeight];", vec![])
+        .await?;
+    assert_eq!(size, serde_json::json!([375, 667]));
+
+    Ok(())
+}
+
+async fn element_value(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    let mut input = c.find(Locator::Id("name")).await?;
+    assert_eq!(input.value().await?.as_deref(), Some("default"));
+    input.clear().await?;
+    input.send_keys("typed").await?;
+    assert_eq!(input.value().await?.as_deref(), Some("typed"));
+    // the attribute still holds the initial value
+    assert_eq!(input.attr("value").await?.as_deref(), Some("default"));
+
+    let mut form = c.find(Locator::Id("form")).await?;
+    assert_eq!(form.value().await?, None);
+
+    Ok(())
+}
+
+async fn key_sequence(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::keys::{Key, KeySequence};
+
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    let mut input = c.find(Locator::Id("name")).await?;
+    let select_all = if cfg!(target_os = "macos") {
+        Key::Meta
+    } else {
+        Key::Control
+    };
+    input
+        .send_key_sequence(
+            KeySequence::new()
+                .chord(&[select_all, Key::Char('a')])
+                .key(Key::Delete)
+                .type_text("replaced")
+                .key(Key::Enter),
+        )
+        .await?;
+    assert_eq!(input.value().await?.as_deref(), Some("replaced"));
+    // pressing enter submitted the form
+    let mut submitted = c.find(Locator::Id("submitted")).await?;
+    assert_eq!(submitted.text().await?, "replaced");
+
+    // the client-level version types into whatever has focus
+    c.send_key_sequence(KeySequence::new().type_text("!"))
+        .await?;
+    assert_eq!(input.value().await?.as_deref(), Some("replaced!"));
+
+    Ok(())
+}
+
+async fn go(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let pages = ["sample_page.html", "other_page.html", "article.html"];
+    let urls: Vec<_> = pages
+        .iter()
+        .map(|page| format!("http://localhost:{}/{}", port, page))
+        .collect();
+    for url in &urls {
+        c.goto(url).await?;
+    }
+
+    c.go(-2).await?;
+    assert_eq!(c.current_url().await?.as_str(), urls[0]);
+    c.go(1).await?;
+    assert_eq!(c.current_url().await?.as_str(), urls[1]);
+    c.go(1).await?;
+    assert_eq!(c.current_url().await?.as_str(), urls[2]);
+
+    match c.go(-10).await {
+        Err(error::CmdError::InvalidArgument(arg, _)) => assert_eq!(arg, "delta"),
+        v => panic!("expected InvalidArgument, got {:?}", v),
+    }
+    assert_eq!(c.current_url().await?.as_str(), urls[2]);
+    Ok(())
+}
+
+async fn goto_with_load_timeout(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let before = c.get_timeouts().await?.page_load;
+
+    let url = format!("http://localhost:{}/hanging.html", port);
+    let start = std::time::Instant::now();
+    c.goto_with_load_timeout(&url, Duration::from_secs(1))
+        .await?;
+    assert!(start.elapsed() < Duration::from_secs(30));
+    assert_eq!(c.current_url().await?.as_str(), url);
+    assert_eq!(
+        c.find(Locator::Id("content")).await?.text().await?,
+        "The page itself is here."
+    );
+    assert_eq!(c.get_timeouts().await?.page_load, before);
+
+    // pages that load in time are not affected
+    c.goto_with_load_timeout(&sample_page_url(port), Duration::from_secs(10))
+        .await?;
+    assert_eq!(c.current_url().await?.as_str(), sample_page_url(port));
+    Ok(())
+}
+
+async fn goto_with_referrer(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let from = format!("http://localhost:{}/other_page.html", port);
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&from).await?;
+
+    c.goto_with_referrer(&url, Some(&from)).await?;
+    assert_eq!(c.current_url().await?.as_str(), url);
+    let referrer = c.execute("return document.referrer;", vec![]).await?;
+    assert_eq!(referrer.as_str(), Some(from.as_str()));
+
+    c.goto(&from).await?;
+    c.goto_with_referrer(&url, None).await?;
+    assert_eq!(c.current_url().await?.as_str(), url);
+    let referrer = c.execute("return document.referrer;", vec![]).await?;
+    assert_eq!(referrer.as_str(), Some(""));
+
+    Ok(())
+}
+
+async fn debug_snapshot(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/article.html", port);
+    c.goto(&url).await?;
+
+    let mut heading = c.find(Locator::Css("h1")).await?;
+    let snapshot = heading.debug_snapshot().await?;
+    assert!(snapshot.outer_html.starts_with("<h1>"));
+    assert_eq!(snapshot.styles["display"], "block");
+    assert!(snapshot.styles.contains_key("font-size"));
+    assert!(snapshot.styles.contains_key("color"));
+    let (_, _, width, height) = snapshot.rect;
+    assert!(width > 0.0 && height > 0.0);
+    assert!(snapshot.screenshot.starts_with(b"\x89PNG"));
+
+    let json = serde_json::to_value(&snapshot)?;
+    assert!(json["screenshot"].is_string());
+    assert_eq!(json["styles"]["display"], "block");
+
+    Ok(())
+}
+
+async fn retry_on_timeout(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    c.set_timeouts(Timeouts {
+        script: Some(Duration::from_millis(100)),
+        ..Default::default()
+    })
+    .await?;
+
+    // the first attempt never finishes, and so times out
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let title = c
+        .retry_on_timeout(3, |mut c| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    c.execute_async("/* never calls back */", vec![]).await?;
+                }
+                c.title().await
+            }
+        })
+        .await?;
+    assert_eq!(title, "Sample Page");
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    // other errors are not retried
+    attempts.store(0, Ordering::SeqCst);
+    let res = c
+        .retry_on_timeout(3, |mut c| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async move { c.find(Locator::Id("does-not-exist")).await }
+        })
+        .await;
+    assert!(res.unwrap_err().is_miss());
+    assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+    // and attempts run out eventually
+    attempts.store(0, Ordering::SeqCst);
+    let res = c
+        .retry_on_timeout(2, |mut c| {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            async move { c.execute_async("/* never calls back */", vec![]).await }
+        })
+        .await;
+    assert!(res.unwrap_err().is_timeout());
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+    Ok(())
+}
+
+async fn maximize_or_set(mut c: Client, _port: u16) -> Result<(), error::CmdError> {
+    // the test browsers are headless, where maximizing does not give a window this large
+    let size = c.maximize_or_set(1400, 1000).await?;
+    assert_eq!(size, (1400, 1000));
+    assert_eq!(c.get_window_size().await?, (1400, 1000));
+
+    Ok(())
+}
+
+async fn find_timeout(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    c.execute(
+        "setTimeout(() => {
+             const late = document.createElement('div');
+             late.id = 'late';
+             document.body.appendChild(late);
+         }, 300);",
+        vec![],
+    )
+    .await?;
+    assert!(c.find(Locator::Id("late")).await.unwrap_err().is_miss());
+    c.find_timeout(Locator::Id("late"), Duration::from_secs(1))
+        .await?;
+
+    // not being found in time is just not being found
+    let start = std::time::Instant::now();
+    let res = c
+        .find_timeout(Locator::Id("never"), Duration::from_millis(300))
+        .await;
+    assert!(res.unwrap_err().is_miss());
+    assert!(start.elapsed() >= Duration::from_millis(300));
+
+    Ok(())
+}
+
+async fn allow_downloads(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let dir = std::env::temp_dir().join(format!("fantoccini-downloads-{}", port));
+    std::fs::create_dir_all(&dir)?;
+    let file = dir.join("downloaded.html");
+    let _ = std::fs::remove_file(&file);
+
+    let url = format!("http://localhost:{}/download.html", port);
+    c.goto(&url).await?;
+    c.allow_downloads(&dir).await?;
+    c.find(Locator::Id("download")).await?.click().await?;
+
+    let start = std::time::Instant::now();
+    while !file.exists() {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "the download never showed up"
+        );
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+
+    Ok(())
+}
+
+async fn find_by_label(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    // associated through the for attribute
+    let mut name = c.find_by_label("Your name").await?;
+    assert_eq!(name.attr("id").await?.as_deref(), Some("name"));
+    name.clear().await?;
+    name.send_keys("Jane").await?;
+    assert_eq!(name.value().await?.as_deref(), Some("Jane"));
+
+    // associated by nesting
+    let mut email = c.find_by_label("Email").await?;
+    assert_eq!(email.attr("id").await?.as_deref(), Some("email"));
+
+    assert!(c.find_by_label("No control").await.unwrap_err().is_miss());
+    assert!(c.find_by_label("Nonexistent").await.unwrap_err().is_miss());
+
+    Ok(())
+}
+
+async fn long_press(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/long_press.html", port);
+    c.goto(&url).await?;
+
+    let mut target = c.find(Locator::Id("target")).await?;
+    let mut action = c.find(Locator::Id("action")).await?;
+    // a short press is not enough
+    target.long_press(Duration::from_millis(100)).await?;
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(action.attr("hidden").await?.is_some());
+
+    target.long_press(Duration::from_millis(700)).await?;
+    assert_eq!(action.attr("hidden").await?, None);
+
+    Ok(())
+}
+
+async fn debug_state(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/iframe_outer.html", port);
+    c.goto(&url).await?;
+    let mut c = c.enter_frame(Some(0)).await?;
+
+    let state = c.debug_state().await;
+    assert_eq!(state.url, Some(url));
+    assert_eq!(state.window_count, Some(1));
+    assert_eq!(state.window_handle, Some(c.window().await?.0));
+    assert_eq!(state.frame_depth, Some(1));
+    assert_eq!(state.active_element, Some("body".to_string()));
+    assert_eq!(state.ready_state, Some("complete".to_string()));
+    assert!(state.title.is_some());
+    assert_eq!(state.errors, vec![]);
+
+    let json = serde_json::to_value(&state)?;
+    assert_eq!(json["frame_depth"], serde_json::json!(1));
+    assert_eq!(json["errors"], serde_json::json!([]));
+
+    Ok(())
+}
+
+async fn find_contains_text(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    let mut button = c
+        .find(Locator::ContainsText {
+            tag: Some("button"),
+            text: "Don't save",
+        })
+        .await?;
+    assert_eq!(button.attr("id").await?.as_deref(), Some("discard"));
+
+    // the innermost match is found, not the containing <div> or <body>
+    let mut button = c
+        .find(Locator::ContainsText {
+            tag: None,
+            text: "save   changes",
+        })
+        .await?;
+    assert_eq!(button.attr("id").await?.as_deref(), Some("discard"));
+
+    // text with both kinds of quotes
+    let mut quote = c
+        .find(Locator::ContainsText {
+            tag: None,
+            text: "said \"don't\"",
+        })
+        .await?;
+    assert_eq!(quote.attr("id").await?.as_deref(), Some("quote"));
+
+    let missing = c
+        .find(Locator::ContainsText {
+            tag: Some("p"),
+            text: "Don't save",
+        })
+        .await;
+    assert!(missing.unwrap_err().is_miss());
+
+    Ok(())
+}
+
+async fn window_handle(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    let main = c.window().await?;
+
+    let popup = c.new_window_handle(false).await?;
+    assert_ne!(popup.handle, main);
+    assert_eq!(c.windows().await?.len(), 2);
+    // reading the popup's size does not leave us in it
+    let (_, _, width, height) = popup.rect(&mut c).await?;
+    assert!(width > 0 && height > 0);
+    assert_eq!(c.window().await?, main);
+
+    popup.switch_to(&mut c).await?;
+    assert_eq!(c.window().await?, popup.handle);
+    c.switch_to_window(main.clone()).await?;
+
+    // closing it from another window leaves us where we were
+    popup.close(&mut c).await?;
+    assert_eq!(c.windows().await?, vec![main.clone()]);
+    assert_eq!(c.window().await?, main);
+
+    Ok(())
+}
+
+async fn set_implicit_wait(mut c: Client, _port: u16) -> Result<(), error::CmdError> {
+    let stored = c.set_implicit_wait(Duration::from_millis(1500)).await?;
+    assert_eq!(stored, Duration::from_millis(1500));
+    assert_eq!(c.get_timeouts().await?.implicit, Some(stored));
+
+    let stored = c.set_implicit_wait(Duration::from_secs(0)).await?;
+    assert_eq!(stored, Duration::from_secs(0));
+
+    Ok(())
+}
+
+async fn screenshot_normalized(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    // pretend to be a high-DPI display
+    c.execute_cdp(
+        "Emulation.setDeviceMetricsOverride",
+        serde_json::json!({
+            "width": 400,
+            "height": 300,
+            "deviceScaleFactor": 2,
+            "mobile": false,
+        }),
+    )
+    .await?;
+
+    let (width, height) = png_size(&c.screenshot().await?);
+    assert_eq!((width, height), (800, 600));
+    let (width, height) = png_size(&c.screenshot_normalized().await?);
+    assert_eq!((width, height), (400, 300));
+
+    Ok(())
+}
+
+async fn attr_prop_css(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const input = document.createElement('input'); \
+         input.id = 'flavor'; \
+         input.value = 'plain'; \
+         input.setAttribute('value', 'plain'); \
+         input.maxLength = 20; \
+         input.style.display = 'inline-block'; \
+         document.body.appendChild(input);",
+        vec![],
+    )
+    .await?;
+
+    let mut input = c.find(Locator::Id("flavor")).await?;
+    input.clear().await?;
+    input.send_keys("oatmeal").await?;
+    // the attribute keeps the initial value, while the property follows what was typed
+    assert_eq!(input.attr("value").await?.as_deref(), Some("plain"));
+    assert_eq!(input.prop("value").await?.as_deref(), Some("oatmeal"));
+    assert_eq!(input.attr("placeholder").await?, None);
+
+    // properties that are not strings are turned into strings
+    assert_eq!(input.prop("maxLength").await?.as_deref(), Some("20"));
+    assert_eq!(input.prop("disabled").await?.as_deref(), Some("false"));
+
+    assert_eq!(input.css_value("display").await?, "inline-block");
+
+    Ok(())
+}
+
+async fn element_state(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+    c.execute(
+        "const form = document.getElementById('form'); \
+         form.insertAdjacentHTML('beforeend', \
+             '<input id=\"agree\" type=\"checkbox\" checked>' + \
+             '<input id=\"locked\" type=\"text\" disabled>' + \
+             '<input id=\"secret\" type=\"text\" style=\"display: none\">');",
+        vec![],
+    )
+    .await?;
+
+    let mut agree = c.find(Locator::Id("agree")).await?;
+    assert!(agree.is_selected().await?);
+    agree.click().await?;
+    let mut agree = c.find(Locator::Id("agree")).await?;
+    assert!(!agree.is_selected().await?);
+
+    let mut name = c.find(Locator::Id("name")).await?;
+    assert!(name.is_enabled().await?);
+    assert!(name.is_displayed().await?);
+    let mut locked = c.find(Locator::Id("locked")).await?;
+    assert!(!locked.is_enabled().await?);
+    let mut secret = c.find(Locator::Id("secret")).await?;
+    assert!(!secret.is_displayed().await?);
+
+    Ok(())
+}
+
+async fn element_contains(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    let mut form = c.find(Locator::Id("form")).await?;
+    let name = c.find(Locator::Id("name")).await?;
+    let submitted = c.find(Locator::Id("submitted")).await?;
+    assert!(form.contains(&name).await?);
+    assert!(form.contains(&form.clone()).await?);
+    assert!(!form.contains(&submitted).await?);
+
+    c.execute("document.getElementById('name').remove();", vec![])
+        .await?;
+    match form.contains(&name).await {
+        Err(error::CmdError::StaleElementReference(_)) => {}
+        r => panic!("expected a stale element error, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn submit_and_wait(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::elements::SubmitOutcome;
+    use fantoccini::Condition;
+
+    let url = format!("http://localhost:{}/login.html", port);
+    c.goto(&url).await?;
+    let rejected = [Condition::Displayed(Locator::Id("error"))];
+
+    let mut form = c.form(Locator::Id("login")).await?;
+    form.set_by_name("username", "jane").await?;
+    form.set_by_name("password", "wrong").await?;
+    let (mut c, outcome) = form
+        .submit_and_wait(&rejected, Duration::from_secs(5))
+        .await?;
+    assert_eq!(outcome, SubmitOutcome::Condition(0));
+
+    let mut form = c.form(Locator::Id("login")).await?;
+    form.set_by_name("password", "secret").await?;
+    let (_, outcome) = form
+        .submit_and_wait(&rejected, Duration::from_secs(5))
+        .await?;
+    match outcome {
+        SubmitOutcome::Navigated(url) => assert_eq!(url.path(), "/other_page.html"),
+        o => panic!("expected to navigate, got {:?}", o),
+    }
+
+    Ok(())
+}
+
+async fn text_and_tag_name(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const p = document.createElement('p'); \
+         p.id = 'spaced'; \
+         p.innerHTML = '  lots   of\\n  space <span style=\"display: none\">hidden</span>'; \
+         document.body.appendChild(p);",
+        vec![],
+    )
+    .await?;
+
+    let mut p = c.find(Locator::Id("spaced")).await?;
+    assert_eq!(p.tag_name().await?, "p");
+    // the rendered text, not the raw text content
+    assert_eq!(p.text().await?, "lots of space");
+    assert!(p.prop("textContent").await?.unwrap().contains("lots   of"));
+
+    Ok(())
+}
+
+async fn stale_element_interaction(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const input = document.createElement('input'); \
+         input.id = 'doomed'; \
+         document.body.appendChild(input);",
+        vec![],
+    )
+    .await?;
+
+    let mut input = c.find(Locator::Id("doomed")).await?;
+    input.send_keys("hello").await?;
+    input.clear().await?;
+    c.execute("document.getElementById('doomed').remove();", vec![])
+        .await?;
+
+    match input.send_keys("hello").await {
+        Err(error::CmdError::StaleElementReference(_)) => {}
+        r => panic!("expected a stale element error, got {:?}", r),
+    }
+    match input.clear().await {
+        Err(error::CmdError::StaleElementReference(_)) => {}
+        r => panic!("expected a stale element error, got {:?}", r),
+    }
+    match input.click().await {
+        Err(error::CmdError::StaleElementReference(_)) => {}
+        r => panic!("expected a stale element error, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn wait_for_url_matching(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/spa.html", port);
+    c.goto(&url).await?;
+
+    let on_dashboard = |url: &url::Url| url.path().starts_with("/dashboard");
+    match c
+        .wait_for_url_matching(on_dashboard, Duration::from_millis(200))
+        .await
+    {
+        Err(error::CmdError::WaitTimeout) => {}
+        r => panic!("expected a timeout, got {:?}", r),
+    }
+
+    c.find(Locator::Id("dashboard_link")).await?.click().await?;
+    let url = c
+        .wait_for_url_matching(on_dashboard, Duration::from_secs(5))
+        .await?;
+    assert_eq!(url.path(), "/dashboard/overview");
+    assert_eq!(
+        c.find(Locator::Id("view")).await?.text().await?,
+        "Dashboard"
+    );
+
+    Ok(())
+}
+
+async fn screenshot_with_padding(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const box = document.createElement('div'); \
+         box.id = 'framed'; \
+         box.style = 'position: absolute; left: 100px; top: 100px; width: 60px; height: 40px; \
+                      background: rgb(0, 0, 255);'; \
+         document.body.appendChild(box);",
+        vec![],
+    )
+    .await?;
+
+    let mut framed = c.find(Locator::Id("framed")).await?;
+    let tight = png_size(&c.screenshot_element(framed.clone()).await?);
+    assert_eq!(tight, (60, 40));
+    let padded = png_size(&framed.screenshot_with_padding(20).await?);
+    assert_eq!(padded, (100, 80));
+
+    Ok(())
+}
+
+async fn pixel_at(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const dot = document.createElement('div'); \
+         dot.style = 'position: fixed; left: 0; top: 0; width: 40px; height: 40px; \
+                      background: rgb(0, 128, 0); z-index: 1000;'; \
+         document.body.appendChild(dot);",
+        vec![],
+    )
+    .await?;
+
+    assert_eq!(c.pixel_at(20, 20).await?, [0, 128, 0, 255]);
+
+    match c.pixel_at(100_000, 20).await {
+        Err(error::CmdError::InvalidArgument(..)) => {}
+        r => panic!(
+            "a pixel outside of the viewport should be rejected, got {:?}",
+            r
+        ),
+    }
+
+    Ok(())
+}
+
+async fn execute_async_or_timeout(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+
+    let start = std::time::Instant::now();
+    let e = c
+        .execute_async_or_timeout("/* never calls back */", vec![], Duration::from_millis(300))
+        .await
+        .expect_err("a script that never calls back should time out");
+    assert!(e.is_timeout(), "expected a timeout, got {:?}", e);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    // the session is not left waiting on the script
+    assert_eq!(c.execute("return 1 + 1;", vec![]).await?, 2);
+
+    let sum = c
+        .execute_async_or_timeout(
+            "const [a, b, done] = arguments; setTimeout(() => done(a + b), 50);",
+            vec![serde_json::json!(2), serde_json::json!(3)],
+            Duration::from_secs(5),
+        )
+        .await?;
+    assert_eq!(sum, 5);
+
+    Ok(())
+}
+
+async fn screenshot_region(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+
+    let png = c.screenshot_region(10, 20, 100, 100).await?;
+    assert_eq!(png_size(&png), (100, 100));
+    // the page is left where it was
+    assert_eq!(c.execute("return window.scrollY;", vec![]).await?, 0);
+
+    match c.screenshot_region(0, 0, 100_000, 100).await {
+        Err(error::CmdError::InvalidArgument(..)) => {}
+        r => panic!(
+            "a region outside of the page should be rejected, got {:?}",
+            r
+        ),
+    }
+
+    Ok(())
+}
+
+async fn wait_for_dom_stable(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/rendering.html", port);
+    c.goto(&url).await?;
+
+    // the ticking clock keeps the page from ever settling down
+    let res = c
+        .wait_for_dom_stable(Duration::from_millis(300), Duration::from_millis(500))
+        .await;
+    assert!(matches!(res, Err(error::CmdError::WaitTimeout)));
+
+    c.wait_for_dom_stable_ignoring(Duration::from_millis(300), Duration::from_secs(5), "#clock")
+        .await?;
+    assert_eq!(c.count(Locator::Css("#items li")).await?, 10);
+
+    Ok(())
+}
+
+async fn execute_get_elements_opt(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/table.html", port);
+    c.goto(&url).await?;
+
+    let cells = c
+        .execute_get_elements_opt(
+            "return ['Alice', 'Dave', 'Carol'].map((name) => \
+                 document.querySelector(`tr[data-name='${name}'] td`));",
+            vec![],
+        )
+        .await?;
+    assert_eq!(cells.len(), 3);
+    assert!(cells[1].is_none());
+    let mut names = Vec::new();
+    for mut cell in cells.into_iter().flatten() {
+        names.push(cell.text().await?);
+    }
+    assert_eq!(names, ["Alice", "Carol"]);
+
+    let res = c.execute_get_elements_opt("return [1];", vec![]).await;
+    assert!(matches!(res, Err(error::CmdError::NotW3C(_))));
+    Ok(())
+}
+
+async fn query_elements_with_data(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/table.html", port);
+    c.goto(&url).await?;
+
+    let rows = c
+        .query_elements_with_data(
+            "return Array.from(document.querySelectorAll(arguments[0]))
+                .map((row) => [row, { name: row.dataset.name, cells: row.cells.length }]);",
+            vec![serde_json::json!("#people tbody tr")],
+        )
+        .await?;
+    let data: Vec<_> = rows.iter().map(|(_, data)| data.clone()).collect();
+    assert_eq!(
+        data,
+        vec![
+            serde_json::json!({ "name": "Alice", "cells": 2 }),
+            serde_json::json!({ "name": "Bob", "cells": 2 }),
+            serde_json::json!({ "name": "Carol", "cells": 2 }),
+        ]
+    );
+
+    // the elements can be used afterwards
+    let (mut bob, _) = rows.into_iter().nth(1).unwrap();
+    let mut link = bob.find(Locator::Css("a")).await?;
+    assert_eq!(link.text().await?, "Bob");
+
+    // a pair must start with an element
+    let res = c.query_elements_with_data("return [[1, 2]];", vec![]).await;
+    assert!(matches!(res, Err(error::CmdError::NotW3C(_))));
+
+    Ok(())
+}
+
+async fn goto_checked(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto_checked(&url).await?;
+
+    let url = format!("http://localhost:{}/no_such_page.html", port);
+    match c.goto_checked(&url).await {
+        Err(error::CmdError::HttpStatus(status)) => assert_eq!(status.as_u16(), 404),
+        r => panic!("expected a 404 error, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn paste(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/clipboard_page.html", port);
+    c.goto(&url).await?;
+
+    c.write_clipboard("pasted text").await?;
+    let mut input = c.find(Locator::Id("paste_input")).await?;
+    input.paste().await?;
+    assert_eq!(input.prop("value").await?.as_deref(), Some("pasted text"));
+
+    c.write_clipboard("first line\nsecond line").await?;
+    let mut area = c.find(Locator::Id("paste_area")).await?;
+    area.paste().await?;
+    assert_eq!(
+        area.prop("value").await?.as_deref(),
+        Some("first line\nsecond line")
+    );
+
+    Ok(())
+}
+
+async fn find_all_stream(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use futures_util::{StreamExt, TryStreamExt};
+
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "for (let i = 0; i < 2000; i++) { \
+             const p = document.createElement('p'); \
+             p.className = 'many'; \
+             p.textContent = 'item ' + i; \
+             document.body.appendChild(p); \
+         }",
+        vec![],
+    )
+    .await?;
+
+    let first: Vec<_> = c
+        .find_all_stream(Locator::Css(".many"))
+        .take(3)
+        .try_collect()
+        .await?;
+    assert_eq!(first.len(), 3);
+    for (i, mut e) in first.into_iter().enumerate() {
+        assert_eq!(e.text().await?, format!("item {}", i));
+    }
+
+    let none: Vec<_> = c
+        .find_all_stream(Locator::Css(".no-such-class"))
+        .try_collect()
+        .await?;
+    assert!(none.is_empty());
+
+    Ok(())
+}
+
+async fn switch_to_default_content(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/nested_iframe.html", port);
+    c.goto(&url).await?;
+
+    let mut c = c.enter_frame(Some(0)).await?;
+    let mut c = c.find(Locator::Id("iframe")).await?.enter_frame().await?;
+    c.find(Locator::Id("iframe_button")).await?;
+    assert_eq!(c.frame_depth().await?, 2);
+
+    let mut c = c.switch_to_default_content().await?;
+    assert_eq!(c.frame_depth().await?, 0);
+    c.find(Locator::Id("iframe_button"))
+        .await
+        .expect_err("should not find the button in the iframe");
+    c.find(Locator::Id("outer_iframe")).await?;
+
+    Ok(())
+}
+
+async fn element_sizes(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/overflow.html", port);
+    c.goto(&url).await?;
+
+    let mut clipped = c.find(Locator::Id("clipped")).await?;
+    assert_eq!(clipped.client_size().await?, (200.0, 100.0));
+    let (width, height) = clipped.scroll_size().await?;
+    assert_eq!(width, 200.0);
+    assert!(
+        height > 100.0,
+        "content should overflow, but is {}px",
+        height
+    );
+
+    let mut fits = c.find(Locator::Id("fits")).await?;
+    assert_eq!(fits.scroll_size().await?, fits.client_size().await?);
+
+    c.execute("document.getElementById('fits').remove();", vec![])
+        .await?;
+    match fits.client_size().await {
+        Err(error::CmdError::StaleElementReference(_)) => {}
+        r => panic!("expected a stale element error, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn drop_file(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/drop_zone.html", port);
+    c.goto(&url).await?;
+
+    let dir = std::env::temp_dir().join(format!("fantoccini-drop-{}", port));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("notes.txt");
+    std::fs::write(&path, "dropped contents").unwrap();
+
+    c.find(Locator::Id("drop_zone"))
+        .await?
+        .drop_file(&path)
+        .await?;
+    let mut uploaded = c.find(Locator::Id("uploaded")).await?;
+    c.wait_for(|c: &mut Client| {
+        let mut c = c.clone();
+        async move {
+            let text = c.find(Locator::Id("uploaded")).await?.text().await?;
+            Ok(!text.is_empty())
+        }
+    })
+    .await?;
+    assert_eq!(uploaded.text().await?, "notes.txt: dropped contents");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
+
+async fn disable_animations(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/animated.html", port);
+    c.goto(&url).await?;
+    let width = "return getComputedStyle(document.getElementById('box')).width;";
+
+    c.disable_animations().await?;
+    c.disable_animations().await?;
+    c.find(Locator::Id("toggle")).await?.click().await?;
+    assert_eq!(c.execute(width, vec![]).await?, "200px");
+
+    // with animations back on, the box takes its time to shrink again
+    c.enable_animations().await?;
+    c.find(Locator::Id("toggle")).await?.click().await?;
+    assert_ne!(c.execute(width, vec![]).await?, "50px");
+    Ok(())
+}
+
+async fn selected_options(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+
+    let mut toppings = c.find(Locator::Id("toppings")).await?;
+    assert!(toppings.selected_options().await?.is_empty());
+    toppings.clone().select_by_value("pineapple").await?;
+    toppings.clone().select_by_value("cheese").await?;
+    assert_eq!(toppings.selected_options().await?, ["cheese", "pineapple"]);
+    assert_eq!(toppings.selected_texts().await?, ["Cheese", "Pineapple"]);
+
+    let mut name = c.find(Locator::Id("name")).await?;
+    match name.selected_options().await {
+        Err(error::CmdError::InvalidArgument(..)) => {}
+        v => panic!("expected InvalidArgument, got {:?}", v),
+    }
+    Ok(())
+}
+
+async fn dispatch_event(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const input = document.createElement('input'); \
+         input.id = 'watched'; \
+         document.body.appendChild(input); \
+         window.events = []; \
+         for (const type of ['change', 'input', 'app:refresh']) { \
+             document.body.addEventListener(type, (e) => { \
+                 window.events.push([e.type, e.constructor.name, e.isTrusted]); \
+             }); \
+         }",
+        vec![],
+    )
+    .await?;
+
+    let mut input = c.find(Locator::Id("watched")).await?;
+    input.dispatch_event("change", true).await?;
+    input.dispatch_event("input", true).await?;
+    input.dispatch_event("app:refresh", true).await?;
+    // does not reach the listener on <body>
+    input.dispatch_event("change", false).await?;
+
+    let events = c.execute("return window.events;", vec![]).await?;
+    assert_eq!(
+        events,
+        serde_json::json!([
+            ["change", "Event", false],
+            ["input", "InputEvent", false],
+            ["app:refresh", "Event", false],
+        ])
+    );
+    Ok(())
+}
+
+async fn css_path(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/table.html", port);
+    c.goto(&url).await?;
+
+    let mut age = c
+        .find(Locator::Css("tr[data-name='Bob'] td:nth-child(2)"))
+        .await?;
+    let path = age.css_path().await?;
+    assert_eq!(path, "#people > tbody > tr:nth-child(2) > td:nth-child(2)");
+    let found = c.find(Locator::Css(&path)).await?;
+    let same = c
+        .execute(
+            "return arguments[0] === arguments[1];",
+            vec![serde_json::to_value(&age)?, serde_json::to_value(&found)?],
+        )
+        .await?;
+    assert_eq!(same, serde_json::Value::Bool(true));
+    assert_eq!(c.find_all(Locator::Css(&path)).await?.len(), 1);
+
+    // without an id to start from, the path goes all the way up to the root
+    let url = format!("http://localhost:{}/other_page.html", port);
+    c.goto(&url).await?;
+    let path = c.find(Locator::Css("body > div")).await?.css_path().await?;
+    assert_eq!(path, "html > body > div");
+    Ok(())
+}
+
+async fn set_value(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/bound_form.html", port);
+    c.goto(&url).await?;
+
+    let mut name = c.find(Locator::Id("name")).await?;
+    name.set_value("Jane Doe").await?;
+    let bio = "x".repeat(1000);
+    c.find(Locator::Id("bio")).await?.set_value(&bio).await?;
+
+    assert_eq!(name.prop("value").await?.as_deref(), Some("Jane Doe"));
+    let state = c.find(Locator::Id("state")).await?.text().await?;
+    assert_eq!(state, format!(r#"{{"name":"Jane Doe","bio":"{}"}}"#, bio));
+    // no keyboard events were fired
+    assert_eq!(c.find(Locator::Id("keys")).await?.text().await?, "0");
+
+    // the value is replaced, not appended to
+    name.set_value("John").await?;
+    assert_eq!(name.prop("value").await?.as_deref(), Some("John"));
+    Ok(())
+}
+
+async fn web_vitals(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    let vitals = c.web_vitals().await?;
+    let fcp = vitals
+        .first_contentful_paint
+        .expect("no first contentful paint");
+    let lcp = vitals
+        .largest_contentful_paint
+        .expect("no largest contentful paint");
+    assert!(lcp > Duration::from_secs(0));
+    assert!(lcp >= fcp);
+    assert!(
+        vitals
+            .cumulative_layout_shift
+            .expect("no cumulative layout shift")
+            < 0.1
+    );
+    // the page has not been interacted with
+    assert_eq!(vitals.first_input_delay, None);
+    Ok(())
+}
+
+async fn resource_timings(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/resources.html", port);
+    c.goto(&url).await?;
+    assert_eq!(
+        c.find(Locator::Id("loaded")).await?.text().await?,
+        "script loaded"
+    );
+
+    let timings = c.resource_timings().await?;
+    let script = timings
+        .iter()
+        .find(|t| t.name.ends_with("/resources.js"))
+        .expect("the script should have a timing entry");
+    assert_eq!(script.initiator_type, "script");
+    assert!(script.duration > Duration::from_secs(0));
+    assert!(timings
+        .iter()
+        .all(|t| t.name.starts_with("http://localhost")));
+
+    Ok(())
+}
+
+async fn cookies(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::cookies::{Cookie, SameSite};
+
+    c.goto(&sample_page_url(port)).await?;
+    c.delete_all_cookies().await?;
+    assert!(c.get_all_cookies().await?.is_empty());
+
+    let mut flavor = Cookie::new("flavor", "oatmeal");
+    flavor.set_path("/").set_same_site(Some(SameSite::Lax));
+    c.add_cookie(flavor).await?;
+    // cookies from the cookie crate work too
+    c.add_cookie(
+        fantoccini::cookie::Cookie::build("session", "abc123")
+            .path("/")
+            .http_only(true)
+            .finish(),
+    )
+    .await?;
+    // cookies added through WebDriver are visible to the page, unless they are HttpOnly
+    assert_eq!(
+        c.execute("return document.cookie;", vec![]).await?,
+        "flavor=oatmeal"
+    );
+
+    let flavor = c.get_named_cookie("flavor").await?;
+    assert_eq!(flavor.value(), "oatmeal");
+    assert_eq!(flavor.path(), Some("/"));
+    assert_eq!(flavor.same_site(), Some(SameSite::Lax));
+    let session = c.get_named_cookie("session").await?;
+    assert!(session.http_only());
+    let mut names: Vec<_> = c
+        .get_all_cookies()
+        .await?
+        .iter()
+        .map(|cookie| cookie.name().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["flavor", "session"]);
+
+    c.delete_cookie("flavor").await?;
+    match c.get_named_cookie("flavor").await {
+        Err(error::CmdError::NoSuchCookie(_)) => {}
+        v => panic!("expected NoSuchCookie, got {:?}", v),
+    }
+    c.delete_all_cookies().await?;
+    assert!(c.get_all_cookies().await?.is_empty());
+    Ok(())
+}
+
+async fn browser(mut c: Client, _port: u16) -> Result<(), error::CmdError> {
+    let browser = c.browser().await?;
+    let version = c
+        .browser_version()
+        .await?
+        .expect("the driver reports a version");
+
+    // the capabilities should agree with what the browser tells pages about itself
+    let ua = c.execute("return navigator.userAgent;", vec![]).await?;
+    let ua = ua.as_str().unwrap();
+    match browser {
+        Browser::Firefox => {
+            assert!(!browser.is_chromium());
+            assert!(ua.contains(&format!("Firefox/{}.", version.major())));
+        }
+        Browser::Chrome => {
+            assert!(browser.is_chromium());
+            assert!(ua.contains(&format!("Chrome/{}.", version.major())));
+        }
+        b => panic!("unexpected browser {:?}", b),
+    }
+    Ok(())
+}
+
+async fn clear_cache(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    async fn script_transfer_size(c: &mut Client) -> Result<u64, error::CmdError> {
+        let timings = c.resource_timings().await?;
+        let script = timings
+            .iter()
+            .find(|t| t.name.ends_with("/resources.js?cache"))
+            .expect("the script should have a timing entry");
+        Ok(script.transfer_size)
+    }
+
+    let url = format!("http://localhost:{}/cached.html", port);
+    c.goto(&url).await?;
+    assert!(script_transfer_size(&mut c).await? > 0);
+
+    // the second visit is served from the cache
+    c.goto_blank(false).await?;
+    c.goto(&url).await?;
+    assert_eq!(script_transfer_size(&mut c).await?, 0);
+
+    c.clear_cache().await?;
+    c.goto_blank(false).await?;
+    c.goto(&url).await?;
+    assert!(script_transfer_size(&mut c).await? > 0);
+
+    c.execute("document.cookie = 'flavor=oatmeal';", vec![])
+        .await?;
+    c.clear_cookies_cdp().await?;
+    assert_eq!(c.execute("return document.cookie;", vec![]).await?, "");
+    Ok(())
+}
+
+async fn tab_order(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/tab_order.html", port);
+    c.goto(&url).await?;
+
+    let mut ids = Vec::new();
+    for mut e in c.tab_order().await? {
+        ids.push(e.attr("id").await?.unwrap_or_default());
+    }
+    assert_eq!(ids, ["email", "first_name", "last_name", "terms", "submit"]);
+    Ok(())
+}
+
+async fn reset_input_state(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::keys::{Key, KeySequence};
+
+    let url = format!("http://localhost:{}/form_controls.html", port);
+    c.goto(&url).await?;
+    let mut name = c.find(Locator::Id("name")).await?;
+    name.clear().await?;
+
+    let keys = KeySequence::new().chord(&[Key::Shift, Key::Char('a')]);
+    name.send_key_sequence(keys).await?;
+    c.release_actions().await?;
+    c.reset_input_state().await?;
+
+    // nothing is left held down, so the text is typed as is
+    name.send_key_sequence(KeySequence::new().type_text("bc"))
+        .await?;
+    assert_eq!(name.value().await?.as_deref(), Some("Abc"));
+
+    Ok(())
+}
+
+async fn screenshot_element_of(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/cards.html", port);
+    c.goto(&url).await?;
+    let ratio = c.execute("return window.devicePixelRatio;", vec![]).await?;
+    let ratio = ratio.as_f64().unwrap();
+    let expected = ((200.0 * ratio) as u32, (100.0 * ratio) as u32);
+
+    let png = c
+        .screenshot_element_of(Locator::Css("#cards .card.featured"), false)
+        .await?;
+    assert!(png.starts_with(b"\x89PNG"));
+    assert_eq!(png_size(&png), expected);
+
+    // the last card is far below the fold
+    let png = c
+        .screenshot_element_of(Locator::Css("#cards .card:last-child"), true)
+        .await?;
+    assert_eq!(png_size(&png), expected);
+
+    match c
+        .screenshot_element_of(Locator::Css("#cards .missing"), false)
+        .await
+    {
+        Err(error::CmdError::NoSuchElement(_)) => {}
+        r => panic!("expected NoSuchElement, got {:?}", r.map(|png| png.len())),
+    }
+
+    Ok(())
+}
+
+async fn mock_geolocation_firefox(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+
+    let position = c
+        .execute_async(
+            r#"const [done] = arguments;
+            navigator.geolocation.getCurrentPosition(
+                (pos) => done([pos.coords.latitude, pos.coords.longitude]),
+                (err) => done(err.message),
+            );"#,
+            vec![],
+        )
+        .await?;
+    assert_eq!(position, serde_json::json!([59.91, 10.75]));
+
+    Ok(())
+}
+
+async fn set_persistent_cookie(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute("document.cookie = 'flavor=oatmeal; max-age=3600';", vec![])
+        .await?;
+    Ok(())
+}
+
+async fn check_persistent_cookie(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    let cookies = c.execute("return document.cookie;", vec![]).await?;
+    assert_eq!(cookies, "flavor=oatmeal");
+    Ok(())
+}
+
+fn profile_dir(browser: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("fantoccini-profile-{}", browser))
+}
+
+/// Check that a cookie set in one session is there in the next one with the same profile.
+fn user_data_dir_test(browser: &'static str) {
+    let dir = profile_dir(browser);
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // the configure closure is expanded once per TLS backend, so it only captures `browser`
+    local_tester_with!(set_persistent_cookie, browser, move |b| {
+        b.user_data_dir(&profile_dir(browser));
+    });
+    local_tester_with!(check_persistent_cookie, browser, move |b| {
+        b.user_data_dir(&profile_dir(browser));
+    });
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+async fn wait_for_any_and_all(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::Condition;
+
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "setTimeout(() => { \
+             const p = document.createElement('p'); \
+             p.id = 'error_toast'; \
+             document.body.appendChild(p); \
+         }, 300); \
+         setTimeout(() => { \
+             const p = document.createElement('p'); \
+             p.id = 'success_toast'; \
+             document.body.appendChild(p); \
+         }, 1500);",
+        vec![],
+    )
+    .await?;
+
+    let toasts = [
+        Condition::Present(Locator::Id("success_toast")),
+        Condition::Present(Locator::Id("error_toast")),
+    ];
+    assert_eq!(c.wait_for_any(&toasts, Duration::from_secs(5)).await?, 1);
+    c.find(Locator::Id("success_toast"))
+        .await
+        .expect_err("the success toast should not be there yet");
+
+    c.wait_for_all(&toasts, Duration::from_secs(5)).await?;
+
+    let never = [
+        Condition::NotPresent(Locator::Id("error_toast")),
+        Condition::Script("return false;"),
+    ];
+    match c.wait_for_any(&never, Duration::from_millis(300)).await {
+        Err(e) if e.is_timeout() => {}
+        r => panic!("expected a timeout, got {:?}", r),
+    }
+    let partly = [toasts[0], never[1]];
+    match c.wait_for_all(&partly, Duration::from_millis(300)).await {
+        Err(e) if e.is_timeout() => {}
+        r => panic!("expected a timeout, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn attributes_of_all(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const list = document.createElement('ul'); \
+         list.id = 'links'; \
+         for (const href of ['/first', null, '/third']) { \
+             const a = document.createElement('a'); \
+             if (href !== null) { a.setAttribute('href', href); } \
+             list.appendChild(a); \
+         } \
+         document.body.appendChild(list);",
+        vec![],
+    )
+    .await?;
+
+    let hrefs = c
+        .attributes_of_all(Locator::Css("#links a"), "href")
+        .await?;
+    assert_eq!(
+        hrefs,
+        vec![Some("/first".to_string()), None, Some("/third".to_string())]
+    );
+    assert!(c
+        .attributes_of_all(Locator::Css("#links nav"), "href")
+        .await?
+        .is_empty());
+
+    Ok(())
+}
+
+async fn register_helpers(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.register_helpers(
+        "var helperRuns = (window.helperRuns || 0) + 1; \
+         function double(x) { return 2 * x; }",
+    );
+    assert_eq!(c.execute("return double(21);", vec![]).await?, 42);
+    assert_eq!(
+        c.execute("return [double(1), helperRuns];", vec![]).await?,
+        serde_json::json!([2, 1])
+    );
+
+    // more helpers can be added, and the page is only given the new ones
+    c.register_helpers("const triple = (x) => 3 * x;");
+    assert_eq!(
+        c.execute("return [triple(1), helperRuns];", vec![]).await?,
+        serde_json::json!([3, 1])
+    );
+    c.register_helpers("const quadruple = (x) => 4 * x;");
+    assert_eq!(
+        c.execute("return [double(1), triple(1), quadruple(1)];", vec![])
+            .await?,
+        serde_json::json!([2, 3, 4])
+    );
+
+    // the helpers are evaluated again on a new page
+    c.goto(&format!("http://localhost:{}/other_page.html", port))
+        .await?;
+    assert_eq!(
+        c.execute("return [triple(2), helperRuns];", vec![]).await?,
+        serde_json::json!([6, 1])
+    );
+    let res = c.execute_async("arguments[0](double(5));", vec![]).await?;
+    assert_eq!(res, 10);
+
+    // errors thrown by the script itself are still reported
+    match c.execute("throw new Error('boom');", vec![]).await {
+        Err(error::CmdError::JavascriptError { message, .. }) => assert!(message.contains("boom")),
+        r => panic!("expected a javascript error, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+async fn goto_blank(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/rendering.html", port);
+    let read_state = "return [document.cookie, localStorage.getItem('visited')];";
+
+    c.goto(&url).await?;
+    c.execute(
+        "document.cookie = 'visited=yes'; localStorage.setItem('visited', 'yes');",
+        vec![],
+    )
+    .await?;
+    c.goto_blank(false).await?;
+    assert_eq!(c.current_url().await?.as_str(), "about:blank");
+    assert!(c.find(Locator::Id("clock")).await.is_err());
+
+    c.goto(&url).await?;
+    assert_eq!(
+        c.execute(read_state, vec![]).await?,
+        serde_json::json!(["visited=yes", "yes"])
+    );
+    c.goto_blank(true).await?;
+    assert_eq!(c.current_url().await?.as_str(), "about:blank");
+
+    c.goto(&url).await?;
+    assert_eq!(
+        c.execute(read_state, vec![]).await?,
+        serde_json::json!(["", null])
+    );
+
+    Ok(())
+}
+
+async fn override_user_agent(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    const UA: &str = "Mozilla/5.0 (fantoccini test) Spoofed/1.0";
+
+    c.goto(&sample_page_url(port)).await?;
+    c.override_user_agent(UA).await?;
+    // the override applies to pages loaded afterwards
+    c.goto(&format!("http://localhost:{}/other_page.html", port))
+        .await?;
+    assert_eq!(c.execute("return navigator.userAgent;", vec![]).await?, UA);
+
+    Ok(())
+}
+
+async fn is_in_viewport(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/cards.html", port);
+    c.goto(&url).await?;
+
+    let mut first = c.find(Locator::Css("#cards .card")).await?;
+    assert!(first.is_in_viewport(false).await?);
+    let mut last = c.find(Locator::Css("#cards .card:last-child")).await?;
+    assert!(!last.is_in_viewport(true).await?);
+
+    // the last card is at the very end of the page
+    c.execute("window.scrollTo(0, document.body.scrollHeight);", vec![])
+        .await?;
+    assert!(last.is_in_viewport(false).await?);
+    assert!(!first.is_in_viewport(true).await?);
+
+    // scroll back up until half of the card is out of view
+    c.execute("window.scrollBy(0, -50);", vec![]).await?;
+    assert!(!last.is_in_viewport(false).await?);
+    assert!(last.is_in_viewport(true).await?);
+
+    Ok(())
+}
+
+async fn frames(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/two_frames.html", port);
+    c.goto(&url).await?;
+
+    let frames = c.frames().await?;
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].index, 0);
+    assert_eq!(frames[0].name.as_deref(), Some("menu"));
+    assert_eq!(frames[0].id, None);
+    assert_eq!(
+        frames[0].src,
+        Some(format!("http://localhost:{}/iframe_inner.html", port))
+    );
+    assert_eq!(frames[1].index, 1);
+    assert_eq!(frames[1].name.as_deref(), Some("content"));
+    assert_eq!(frames[1].id.as_deref(), Some("content_frame"));
+    assert_eq!(
+        frames[1].src,
+        Some(format!("http://localhost:{}/other_page.html", port))
+    );
+
+    // the index is the one to enter the frame with
+    let content = frames.iter().find(|f| f.name.as_deref() == Some("content"));
+    let mut c = c.enter_frame(Some(content.unwrap().index)).await?;
+    assert!(c
+        .execute("return location.href;", vec![])
+        .await?
+        .as_str()
+        .unwrap()
+        .ends_with("/other_page.html"));
+    assert!(c.frames().await?.is_empty());
+
+    Ok(())
+}
+
+#[cfg(feature = "test-utils")]
+async fn result_ext(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::test_utils::ClientResultExt;
+
+    c.goto(&sample_page_url(port)).await?;
+    let dir = std::env::temp_dir().join(format!("fantoccini-result-ext-{}", port));
+    std::fs::create_dir_all(&dir).unwrap();
+    let png = dir.join("failure.png");
+    let html = dir.join("failure.html");
+
+    // nothing is saved on success
+    c.find(Locator::Id("navigation"))
+        .await
+        .or_screenshot(&mut c, &png)
+        .await?;
+    assert!(!png.exists());
+
+    let res = c.find(Locator::Id("no_such_element")).await;
+    match res.or_screenshot(&mut c, &png).await {
+        Err(error::CmdError::NoSuchElement(_)) => {}
+        r => panic!("expected the original error, got {:?}", r),
+    }
+    assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+
+    let res = c.find(Locator::Id("no_such_element")).await;
+    res.or_page_source(&mut c, &html)
+        .await
+        .expect_err("the error should be returned");
+    assert!(std::fs::read_to_string(&html)
+        .unwrap()
+        .contains("id=\"navigation\""));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
+
+async fn set_page_basic_auth(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/protected/sample_page.html", port);
+    c.goto(&sample_page_url(port)).await?;
+
+    c.set_page_basic_auth("user", "pass").await?;
+    c.goto(&url).await?;
+    assert_eq!(c.current_url().await?.as_str(), url);
+    c.find(Locator::Id("navigation")).await?;
+
+    Ok(())
+}
+
+async fn capture_dom_json(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    use fantoccini::DomNode;
+
+    c.goto(&sample_page_url(port)).await?;
+    let json = c.capture_dom_json().await?;
+    assert_eq!(json["tag"], "html");
+    assert_eq!(json["attributes"]["lang"], "en");
+
+    // html > body > div.vertical > nav#navigation > a#other_page_id
+    let body = &json["children"][1];
+    assert_eq!(body["tag"], "body");
+    let nav = &body["children"][0]["children"][0];
+    assert_eq!(nav["attributes"]["id"], "navigation");
+    assert_eq!(
+        nav["children"][0],
+        serde_json::json!({
+            "tag": "a",
+            "attributes": { "href": "other_page.html", "id": "other_page_id" },
+            "children": ["Other Page"],
+        })
+    );
+
+    let root: DomNode = serde_json::from_value(json.clone())?;
+    match root {
+        DomNode::Element {
+            ref tag,
+            ref children,
+            ..
+        } => {
+            assert_eq!(tag, "html");
+            assert_eq!(children.len(), 2);
+        }
+        DomNode::Text(_) => panic!("the root should be an element"),
+    }
+    assert_eq!(serde_json::to_value(&root)?, json);
+
+    Ok(())
+}
+
+async fn wait_for_attribute_on_locator(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    let url = format!("http://localhost:{}/sample_page.html", port);
+    c.goto(&url).await?;
+    // re-render the widget from scratch a few times, only the last of which is loaded
+    c.execute(
+        r#"
+        const render = (loaded) => {
+            const widget = document.createElement('div');
+            widget.id = 'widget';
+            widget.dataset.loaded = String(loaded);
+            const old = document.getElementById('widget');
+            if (old) {
+                old.replaceWith(widget);
+            } else {
+                document.body.appendChild(widget);
+            }
+        };
+        render(false);
+        for (let i = 1; i <= 5; i++) {
+            setTimeout(() => render(i === 5), i * 150);
+        }
+        "#,
+        vec![],
+    )
+    .await?;
+
+    let mut widget = c
+        .wait_for_attribute(
+            Locator::Id("widget"),
+            "data-loaded",
+            |v| v == Some("true"),
+            Duration::from_secs(10),
+        )
+        .await?;
+    assert_eq!(widget.attr("data-loaded").await?.as_deref(), Some("true"));
+
+    let res = c
+        .wait_for_attribute(
+            Locator::Id("widget"),
+            "data-loaded",
+            |v| v == Some("false"),
+            Duration::from_millis(300),
+        )
+        .await;
+    assert!(matches!(res, Err(error::CmdError::WaitTimeout)));
+    Ok(())
+}
+
+async fn wait_for_window_count(mut c: Client, port: u16) -> Result<(), error::CmdError> {
+    c.goto(&sample_page_url(port)).await?;
+    c.execute(
+        "const link = document.createElement('a'); \
+         link.id = 'popup_link'; \
+         link.href = '#'; \
+         link.textContent = 'Open later'; \
+         link.onclick = () => { \
+             setTimeout(() => window.open('other_page.html', '_blank'), 500); \
+             return false; \
+         }; \
+         document.body.appendChild(link);",
+        vec![],
+    )
+    .await?;
+
+    c.find(Locator::Id("popup_link")).await?.click().await?;
+    assert_eq!(c.windows().await?.len(), 1);
+    c.wait_for_window_count(2, Duration::from_secs(5)).await?;
+    assert_eq!(c.windows().await?.len(), 2);
+
+    match c.wait_for_window_count(3, Duration::from_millis(300)).await {
+        Err(e) if e.is_timeout() => {}
+        r => panic!("expected a timeout, got {:?}", r),
+    }
+
+    Ok(())
+}
+
+mod firefox {
+    use super::*;
+    #[test]
+    #[serial]
+    fn navigate_to_other_page() {
+        local_tester!(goto, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_and_click_link_test() {
+        local_tester!(find_and_click_link, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn get_active_element_test() {
+        local_tester!(get_active_element, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn serialize_element_test() {
+        local_tester!(serialize_element, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn iframe_test() {
+        local_tester!(iframe_switch, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn new_window_test() {
+        tester!(new_window, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn new_window_switch_test() {
+        tester!(new_window_switch, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn new_tab_switch_test() {
+        tester!(new_tab_switch, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn close_window_test() {
+        tester!(close_window, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn double_close_window_test() {
+        tester!(close_window_twice_errors, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn set_by_name_textarea_test() {
+        local_tester!(set_by_name_textarea, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn stale_element_test() {
+        local_tester!(stale_element, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn select_by_index_test() {
+        local_tester!(select_by_index, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn select_by_label_test() {
+        local_tester!(select_by_label, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn resolve_execute_async_value_test() {
+        local_tester!(resolve_execute_async_value, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wrapped_scripts_test() {
+        local_tester_with!(wrapped_scripts_do_not_leak, "firefox", |b| {
+            b.wrap_scripts(true);
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn windows_info_test() {
+        local_tester!(windows_info, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_network_idle_test() {
+        local_tester!(wait_for_network_idle, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn javascript_error_test() {
+        local_tester!(javascript_error, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn scroll_to_bottom_test() {
+        local_tester!(scroll_to_bottom, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn frame_depth_test() {
+        local_tester!(frame_depth, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn hover_test() {
+        local_tester!(hover, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn location_in_page_test() {
+        local_tester!(location_in_page, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_by_test_id_test() {
+        local_tester_with!(find_by_test_id, "firefox", |b| {
+            b.test_id_attribute("data-cy");
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_gone_test() {
+        local_tester!(wait_for_gone, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_attribute_test() {
+        local_tester!(wait_for_attribute, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn issue_nowait_test() {
+        local_tester!(issue_nowait, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn scrape_test() {
+        local_tester!(scrape, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn with_timeouts_test() {
+        local_tester!(with_timeouts, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn execute_in_frame_test() {
+        local_tester!(execute_in_frame, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn restart_test() {
+        local_tester!(restart, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn readable_text_test() {
+        local_tester!(readable_text, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn count_test() {
+        local_tester!(count, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn element_value_test() {
+        local_tester!(element_value, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn key_sequence_test() {
+        local_tester!(key_sequence, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn goto_with_referrer_test() {
+        local_tester!(goto_with_referrer, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn goto_with_load_timeout_test() {
+        local_tester!(goto_with_load_timeout, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn cookies_test() {
+        local_tester!(cookies, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn browser_test() {
+        local_tester!(browser, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn go_test() {
+        local_tester!(go, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn debug_snapshot_test() {
+        local_tester!(debug_snapshot, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn retry_on_timeout_test() {
+        local_tester!(retry_on_timeout, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn maximize_or_set_test() {
+        local_tester!(maximize_or_set, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_timeout_test() {
+        local_tester!(find_timeout, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_by_label_test() {
+        local_tester!(find_by_label, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn long_press_test() {
+        local_tester!(long_press, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn debug_state_test() {
+        local_tester!(debug_state, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_contains_text_test() {
+        local_tester!(find_contains_text, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn window_handle_test() {
+        local_tester!(window_handle, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn set_implicit_wait_test() {
+        local_tester!(set_implicit_wait, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_dom_stable_test() {
+        local_tester!(wait_for_dom_stable, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn query_elements_with_data_test() {
+        local_tester!(query_elements_with_data, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn execute_get_elements_opt_test() {
+        local_tester!(execute_get_elements_opt, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn goto_checked_test() {
+        local_tester!(goto_checked, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn find_all_stream_test() {
+        local_tester!(find_all_stream, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn switch_to_default_content_test() {
+        local_tester!(switch_to_default_content, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn element_sizes_test() {
+        local_tester!(element_sizes, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn drop_file_test() {
+        local_tester!(drop_file, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn set_value_test() {
+        local_tester!(set_value, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn screenshot_region_test() {
+        local_tester!(screenshot_region, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn pixel_at_test() {
+        local_tester!(pixel_at, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn screenshot_with_padding_test() {
+        local_tester!(screenshot_with_padding, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn execute_async_or_timeout_test() {
+        local_tester!(execute_async_or_timeout, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_url_matching_test() {
+        local_tester!(wait_for_url_matching, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn stale_element_interaction_test() {
+        local_tester!(stale_element_interaction, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn text_and_tag_name_test() {
+        local_tester!(text_and_tag_name, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn submit_and_wait_test() {
+        local_tester!(submit_and_wait, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn element_state_test() {
+        local_tester!(element_state, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn element_contains_test() {
+        local_tester!(element_contains, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn attr_prop_css_test() {
+        local_tester!(attr_prop_css, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn css_path_test() {
+        local_tester!(css_path, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn selected_options_test() {
+        local_tester!(selected_options, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn dispatch_event_test() {
+        local_tester!(dispatch_event, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn disable_animations_test() {
+        local_tester!(disable_animations, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn resource_timings_test() {
+        local_tester!(resource_timings, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn reset_input_state_test() {
+        local_tester!(reset_input_state, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn tab_order_test() {
+        local_tester!(tab_order, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn screenshot_element_of_test() {
+        local_tester!(screenshot_element_of, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_any_and_all_test() {
+        local_tester!(wait_for_any_and_all, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn attributes_of_all_test() {
+        local_tester!(attributes_of_all, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn register_helpers_test() {
+        local_tester!(register_helpers, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn goto_blank_test() {
+        local_tester!(goto_blank, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn is_in_viewport_test() {
+        local_tester!(is_in_viewport, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn frames_test() {
+        local_tester!(frames, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn capture_dom_json_test() {
+        local_tester!(capture_dom_json, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_window_count_test() {
+        local_tester!(wait_for_window_count, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn wait_for_attribute_on_locator_test() {
+        local_tester!(wait_for_attribute_on_locator, "firefox");
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    #[serial]
+    fn result_ext_test() {
+        local_tester!(result_ext, "firefox");
+    }
+
+    #[test]
+    #[serial]
+    fn mock_geolocation_firefox_test() {
+        local_tester_with!(mock_geolocation_firefox, "firefox", |b| {
+            b.mock_geolocation_firefox(59.91, 10.75);
+        });
+    }
+
+    #[test]
+    #[serial]
+    fn user_data_dir_test() {
+        super::user_data_dir_test("firefox");
+    }
+}
+
+mod chrome {
+    use super::*;
+    #[test]
+    fn navigate_to_other_page() {
+        local_tester!(goto, "chrome");
+    }
+
+    #[test]
+    fn find_and_click_link_test() {
+        local_tester!(find_and_click_link, "chrome");
+    }
+
+    #[test]
+    fn get_active_element_test() {
+        local_tester!(get_active_element, "chrome");
+    }
+
+    #[test]
+    fn serialize_element_test() {
+        local_tester!(serialize_element, "chrome");
+    }
+
+    #[test]
+    fn iframe_test() {
+        local_tester!(iframe_switch, "chrome");
+    }
+
+    #[test]
+    fn new_window_test() {
+        tester!(new_window, "chrome");
+    }
+
+    #[test]
+    fn new_window_switch_test() {
+        tester!(new_window_switch, "chrome");
+    }
+
+    #[test]
+    fn new_tab_test() {
+        tester!(new_tab_switch, "chrome");
+    }
+
+    #[test]
+    fn close_window_test() {
+        tester!(close_window, "chrome");
+    }
+
+    #[test]
+    fn double_close_window_test() {
+        tester!(close_window_twice_errors, "chrome");
+    }
+
+    #[test]
+    fn set_by_name_textarea_test() {
+        local_tester!(set_by_name_textarea, "chrome");
+    }
+
+    #[test]
+    #[serial]
+    fn select_by_label_test() {
+        local_tester!(select_by_label, "chrome");
+    }
+
+    #[test]
+    fn select_by_index_label() {
+        local_tester!(select_by_index, "chrome");
+    }
+
+    #[test]
+    fn wrapped_scripts_test() {
+        local_tester_with!(wrapped_scripts_do_not_leak, "chrome", |b| {
+            b.wrap_scripts(true);
+        });
+    }
+
+    #[test]
+    fn windows_info_test() {
+        local_tester!(windows_info, "chrome");
+    }
+
+    #[test]
+    fn wait_for_network_idle_test() {
+        local_tester!(wait_for_network_idle, "chrome");
+    }
+
+    #[test]
+    fn clipboard_test() {
+        local_tester!(clipboard, "chrome");
+    }
+
+    #[test]
+    fn emulate_media_test() {
+        local_tester!(emulate_media, "chrome");
+    }
+
+    #[test]
+    fn javascript_error_test() {
+        local_tester!(javascript_error, "chrome");
+    }
+
+    #[test]
+    fn scroll_to_bottom_test() {
+        local_tester!(scroll_to_bottom, "chrome");
+    }
+
+    #[test]
+    fn frame_depth_test() {
+        local_tester!(frame_depth, "chrome");
+    }
+
+    #[test]
+    fn hover_test() {
+        local_tester!(hover, "chrome");
+    }
+
+    #[test]
+    fn location_in_page_test() {
+        local_tester!(location_in_page, "chrome");
+    }
+
+    #[test]
+    fn find_by_test_id_test() {
+        local_tester_with!(find_by_test_id, "chrome", |b| {
+            b.test_id_attribute("data-cy");
+        });
+    }
+
+    #[test]
+    fn wait_for_gone_test() {
+        local_tester!(wait_for_gone, "chrome");
+    }
+
+    #[test]
+    fn screenshot_surface_test() {
+        local_tester!(screenshot_surface, "chrome");
+    }
+
+    #[test]
+    fn screenshot_region_test() {
+        local_tester!(screenshot_region, "chrome");
+    }
+
+    #[test]
+    fn pixel_at_test() {
+        local_tester!(pixel_at, "chrome");
+    }
+
+    #[test]
+    fn screenshot_with_padding_test() {
+        local_tester!(screenshot_with_padding, "chrome");
+    }
+
+    #[test]
+    fn execute_async_or_timeout_test() {
+        local_tester!(execute_async_or_timeout, "chrome");
+    }
+
+    #[test]
+    fn wait_for_url_matching_test() {
+        local_tester!(wait_for_url_matching, "chrome");
+    }
+
+    #[test]
+    fn stale_element_interaction_test() {
+        local_tester!(stale_element_interaction, "chrome");
+    }
+
+    #[test]
+    fn text_and_tag_name_test() {
+        local_tester!(text_and_tag_name, "chrome");
+    }
+
+    #[test]
+    fn submit_and_wait_test() {
+        local_tester!(submit_and_wait, "chrome");
+    }
+
+    #[test]
+    fn element_state_test() {
+        local_tester!(element_state, "chrome");
+    }
+
+    #[test]
+    fn element_contains_test() {
+        local_tester!(element_contains, "chrome");
+    }
+
+    #[test]
+    fn attr_prop_css_test() {
+        local_tester!(attr_prop_css, "chrome");
+    }
+
+    #[test]
+    fn wait_for_attribute_test() {
+        local_tester!(wait_for_attribute, "chrome");
+    }
+
+    #[test]
+    fn issue_nowait_test() {
+        local_tester!(issue_nowait, "chrome");
+    }
+
+    #[test]
+    fn mock_geolocation_test() {
+        local_tester!(mock_geolocation, "chrome");
+    }
+
+    #[test]
+    fn scrape_test() {
+        local_tester!(scrape, "chrome");
+    }
+
+    #[test]
+    fn with_timeouts_test() {
+        local_tester!(with_timeouts, "chrome");
+    }
+
+    #[test]
+    fn execute_in_frame_test() {
+        local_tester!(execute_in_frame, "chrome");
+    }
+
+    #[test]
+    fn restart_test() {
+        local_tester!(restart, "chrome");
+    }
+
+    #[test]
+    fn readable_text_test() {
+        local_tester!(readable_text, "chrome");
+    }
+
+    #[test]
+    fn count_test() {
+        local_tester!(count, "chrome");
+    }
+
+    #[test]
+    fn set_viewport_test() {
+        local_tester!(set_viewport, "chrome");
+    }
+
+    #[test]
+    fn element_value_test() {
+        local_tester!(element_value, "chrome");
+    }
+
+    #[test]
+    fn key_sequence_test() {
+        local_tester!(key_sequence, "chrome");
+    }
+
+    #[test]
+    fn goto_with_referrer_test() {
+        local_tester!(goto_with_referrer, "chrome");
+    }
+
+    #[test]
+    fn goto_with_load_timeout_test() {
+        local_tester!(goto_with_load_timeout, "chrome");
+    }
+
+    #[test]
+    fn cookies_test() {
+        local_tester!(cookies, "chrome");
+    }
+
+    #[test]
+    fn browser_test() {
+        local_tester!(browser, "chrome");
+    }
+
+    #[test]
+    fn go_test() {
+        local_tester!(go, "chrome");
+    }
+
+    #[test]
+    fn debug_snapshot_test() {
+        local_tester!(debug_snapshot, "chrome");
+    }
+
+    #[test]
+    fn retry_on_timeout_test() {
+        local_tester!(retry_on_timeout, "chrome");
+    }
+
+    #[test]
+    fn maximize_or_set_test() {
+        local_tester!(maximize_or_set, "chrome");
+    }
+
+    #[test]
+    fn find_timeout_test() {
+        local_tester!(find_timeout, "chrome");
+    }
+
+    #[test]
+    fn allow_downloads_test() {
+        local_tester!(allow_downloads, "chrome");
+    }
+
+    #[test]
+    fn find_by_label_test() {
+        local_tester!(find_by_label, "chrome");
+    }
+
+    #[test]
+    fn long_press_test() {
+        local_tester!(long_press, "chrome");
+    }
+
+    #[test]
+    fn debug_state_test() {
+        local_tester!(debug_state, "chrome");
+    }
+
+    #[test]
+    fn find_contains_text_test() {
+        local_tester!(find_contains_text, "chrome");
+    }
+
+    #[test]
+    fn window_handle_test() {
+        local_tester!(window_handle, "chrome");
+    }
+
+    #[test]
+    fn set_implicit_wait_test() {
+        local_tester!(set_implicit_wait, "chrome");
+    }
+
+    #[test]
+    fn screenshot_normalized_test() {
+        local_tester!(screenshot_normalized, "chrome");
+    }
+
+    #[test]
+    fn wait_for_dom_stable_test() {
+        local_tester!(wait_for_dom_stable, "chrome");
+    }
+
+    #[test]
+    fn query_elements_with_data_test() {
+        local_tester!(query_elements_with_data, "chrome");
+    }
+
+    #[test]
+    fn execute_get_elements_opt_test() {
+        local_tester!(execute_get_elements_opt, "chrome");
+    }
+
+    #[test]
+    fn goto_checked_test() {
+        local_tester!(goto_checked, "chrome");
+    }
+
+    #[test]
+    fn find_all_stream_test() {
+        local_tester!(find_all_stream, "chrome");
+    }
+
+    #[test]
+    fn switch_to_default_content_test() {
+        local_tester!(switch_to_default_content, "chrome");
+    }
+
+    #[test]
+    fn element_sizes_test() {
+        local_tester!(element_sizes, "chrome");
+    }
+
+    #[test]
+    fn drop_file_test() {
+        local_tester!(drop_file, "chrome");
+    }
+
+    #[test]
+    fn set_value_test() {
+        local_tester!(set_value, "chrome");
+    }
+
+    #[test]
+    fn css_path_test() {
+        local_tester!(css_path, "chrome");
+    }
+
+    #[test]
+    fn selected_options_test() {
+        local_tester!(selected_options, "chrome");
+    }
+
+    #[test]
+    fn dispatch_event_test() {
+        local_tester!(dispatch_event, "chrome");
+    }
+
+    #[test]
+    fn disable_animations_test() {
+        local_tester!(disable_animations, "chrome");
+    }
+
+    #[test]
+    fn resource_timings_test() {
+        local_tester!(resource_timings, "chrome");
+    }
+
+    #[test]
+    fn web_vitals_test() {
+        local_tester!(web_vitals, "chrome");
+    }
+
+    #[test]
+    fn clear_cache_test() {
+        local_tester!(clear_cache, "chrome");
+    }
+
+    #[test]
+    fn user_data_dir_test() {
+        super::user_data_dir_test("chrome");
+    }
+
+    #[test]
+    fn reset_input_state_test() {
+        local_tester!(reset_input_state, "chrome");
+    }
+
+    #[test]
+    fn tab_order_test() {
+        local_tester!(tab_order, "chrome");
+    }
+
+    #[test]
+    fn screenshot_element_of_test() {
+        local_tester!(screenshot_element_of, "chrome");
+    }
+
+    #[test]
+    fn wait_for_any_and_all_test() {
+        local_tester!(wait_for_any_and_all, "chrome");
+    }
+
+    #[test]
+    fn attributes_of_all_test() {
+        local_tester!(attributes_of_all, "chrome");
+    }
+
+    #[test]
+    fn register_helpers_test() {
+        local_tester!(register_helpers, "chrome");
+    }
+
+    #[test]
+    fn goto_blank_test() {
+        local_tester!(goto_blank, "chrome");
+    }
+
+    #[test]
+    fn is_in_viewport_test() {
+        local_tester!(is_in_viewport, "chrome");
+    }
+
+    #[test]
+    fn frames_test() {
+        local_tester!(frames, "chrome");
+    }
+
+    #[test]
+    fn capture_dom_json_test() {
+        local_tester!(capture_dom_json, "chrome");
+    }
+
+    #[test]
+    fn wait_for_window_count_test() {
+        local_tester!(wait_for_window_count, "chrome");
+    }
+
+    #[test]
+    fn wait_for_attribute_on_locator_test() {
+        local_tester!(wait_for_attribute_on_locator, "chrome");
+    }
+
+    #[test]
+    fn set_page_basic_auth_test() {
+        local_tester!(set_page_basic_auth, "chrome");
+    }
+
+    #[test]
+    #[cfg(feature = "test-utils")]
+    fn result_ext_test() {
+        local_tester!(result_ext, "chrome");
+    }
+
+    #[test]
+    fn override_user_agent_test() {
+        local_tester!(override_user_agent, "chrome");
+    }
+
+    #[test]
+    fn paste_test() {
+        local_tester!(paste, "chrome");
+    }
+
+    #[test]
+    fn set_cookie_cdp_test() {
+        local_tester!(set_cookie_cdp, "chrome");
     }
 }
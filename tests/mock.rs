@@ -0,0 +1,640 @@
+//! Tests that run against a mock WebDriver server rather than a real browser.
+use fantoccini::keys::{Key, KeySequence};
+use fantoccini::{Browser, ClientBuilder, Protocol, Timeouts};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use serde_json::Value as Json;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const CREDENTIALS: &str = "Basic dXNlcjpwYXNz";
+
+/// A request received by the mock driver.
+#[derive(Debug)]
+struct Received {
+    method: Method,
+    path: String,
+    auth: Option<String>,
+    body: Json,
+}
+
+/// A mock WebDriver server, and the requests it has received so far.
+#[derive(Clone, Default)]
+struct MockDriver {
+    received: Arc<Mutex<Vec<Received>>>,
+    sessions: Arc<AtomicUsize>,
+    require_auth: bool,
+}
+
+impl MockDriver {
+    fn requiring_auth() -> Self {
+        MockDriver {
+            require_auth: true,
+            ..Default::default()
+        }
+    }
+
+    /// Start serving on a random port, and return the URL to connect to.
+    fn start(&self) -> String {
+        self.start_on(0)
+    }
+
+    /// Start serving on the given port, and return the URL to connect to.
+    fn start_on(&self, port: u16) -> String {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
+        let driver = self.clone();
+        let server = Server::bind(&socket_addr).serve(make_service_fn(move |_| {
+            let driver = driver.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |req| driver.clone().handle(req))) }
+        }));
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        format!("http://{}", addr)
+    }
+
+    /// Answer just enough of the WebDriver protocol to create, use, and delete a session.
+    async fn handle(self, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+        let (parts, body) = req.into_parts();
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        let auth = parts
+            .headers
+            .get(hyper::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let authorized = !self.require_auth || auth.as_deref() == Some(CREDENTIALS);
+        self.received.lock().unwrap().push(Received {
+            method: parts.method.clone(),
+            path: parts.uri.path().to_string(),
+            auth,
+            body: serde_json::from_slice(&body).unwrap_or(Json::Null),
+        });
+        if !authorized {
+            return Ok(Response::builder()
+                .status(StatusCode::UNAUTHORIZED)
+                .body(Body::from("unauthorized"))
+                .unwrap());
+        }
+
+        let path = parts.uri.path();
+        let value = match (&parts.method, path.strip_prefix("/session")) {
+            (&Method::POST, Some("")) => {
+                // the first session is called "mock", and later ones "mock1", "mock2", ...
+                let id = match self.sessions.fetch_add(1, Ordering::SeqCst) {
+                    0 => "mock".to_string(),
+                    n => format!("mock{}", n),
+                };
+                let capabilities = serde_json::json!({
+                    "browserName": "msedge",
+                    "browserVersion": "120.0.2210.61",
+                });
+                serde_json::json!({ "sessionId": id, "capabilities": capabilities })
+            }
+            (&Method::GET, Some(rest)) if rest.ends_with("/url") => {
+                serde_json::json!("about:blank")
+            }
+            (&Method::POST, Some(rest)) if rest.ends_with("/actions") => {
+                // fail every chain of actions, as if its target had gone away part of the way through
+                let error = serde_json::json!({
+                    "error": "move target out of bounds",
+                    "message": "mock driver cannot perform actions",
+                    "stacktrace": "",
+                });
+                return Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .header(hyper::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "value": error }).to_string(),
+                    ))
+                    .unwrap());
+            }
+            (&Method::GET, Some(rest)) if rest.contains("/cookie/") => {
+                // there are no cookies to be found
+                let error = serde_json::json!({
+                    "error": "no such cookie",
+                    "message": "mock driver has no cookies",
+                    "stacktrace": "",
+                });
+                return Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .header(hyper::header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "value": error }).to_string(),
+                    ))
+                    .unwrap());
+            }
+            (&Method::POST, Some(rest)) if rest.ends_with("/cookie") => Json::Null,
+            (&Method::GET, Some(rest)) if rest.ends_with("/cookie") => serde_json::json!([{
+                "name": "session",
+                "value": "let-me-in",
+                "domain": "127.0.0.1",
+                "path": "/",
+                "secure": false,
+                "httpOnly": true,
+            }]),
+            (&Method::POST, Some(rest)) if rest.ends_with("/timeouts") => Json::Null,
+            (&Method::DELETE, Some(rest)) if rest.starts_with("/mock") => Json::Null,
+            _ => {
+                return Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())
+                    .unwrap())
+            }
+        };
+        let body = serde_json::json!({ "value": value }).to_string();
+        Ok(Response::builder()
+            .header(hyper::header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap())
+    }
+}
+
+fn builder() -> ClientBuilder<hyper::client::HttpConnector> {
+    ClientBuilder::new(hyper::client::HttpConnector::new())
+}
+
+/// The capabilities in the given JSON object.
+fn json_caps(caps: Json) -> serde_json::Map<String, Json> {
+    match caps {
+        Json::Object(caps) => caps,
+        caps => panic!("capabilities must be a JSON object, got {}", caps),
+    }
+}
+
+#[tokio::test]
+async fn basic_auth() {
+    let driver = MockDriver::requiring_auth();
+    let url = driver.start();
+
+    let mut c = builder()
+        .basic_auth("user", "pass")
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    assert_eq!(c.current_url().await.unwrap().as_str(), "about:blank");
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    assert_eq!(received.len(), 3);
+    assert!(received
+        .iter()
+        .all(|req| req.auth.as_deref() == Some(CREDENTIALS)));
+}
+
+#[tokio::test]
+async fn basic_auth_missing() {
+    let driver = MockDriver::requiring_auth();
+    let url = driver.start();
+
+    builder()
+        .connect(&url)
+        .await
+        .expect_err("mock driver should reject unauthenticated sessions");
+    let received = driver.received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].auth, None);
+}
+
+#[tokio::test]
+async fn merge_capabilities() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut caps = serde_json::Map::new();
+    caps.insert(
+        "proxy".to_string(),
+        serde_json::json!({ "proxyType": "manual", "httpProxy": "localhost:8080" }),
+    );
+    let mut c = builder()
+        .capabilities(caps)
+        .merge_capabilities(json_caps(serde_json::json!({
+            "proxy": { "noProxy": ["example.com"] },
+            "goog:chromeOptions": { "args": ["--headless"] },
+        })))
+        .merge_capabilities(json_caps(serde_json::json!({
+            "goog:chromeOptions": { "binary": "/usr/bin/chromium" },
+        })))
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    let new_session = received
+        .iter()
+        .find(|req| req.method == Method::POST && req.path == "/session")
+        .expect("no session was requested");
+    let caps = &new_session.body["capabilities"]["alwaysMatch"];
+    assert_eq!(
+        caps["proxy"],
+        serde_json::json!({
+            "proxyType": "manual",
+            "httpProxy": "localhost:8080",
+            "noProxy": ["example.com"],
+        })
+    );
+    assert_eq!(
+        caps["goog:chromeOptions"]["args"],
+        serde_json::json!(["--headless"])
+    );
+    assert_eq!(
+        caps["goog:chromeOptions"]["binary"],
+        serde_json::json!("/usr/bin/chromium")
+    );
+}
+
+#[tokio::test]
+async fn accept_insecure_certs() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .accept_insecure_certs(true)
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    assert_eq!(
+        received[0].body["capabilities"]["alwaysMatch"]["acceptInsecureCerts"],
+        serde_json::json!(true)
+    );
+}
+
+#[tokio::test]
+async fn capabilities_json() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut b = builder();
+    b.merge_capabilities(json_caps(serde_json::json!({
+        "browserName": "chrome",
+        "goog:chromeOptions": { "args": ["--headless"] },
+    })))
+    .merge_capabilities(json_caps(serde_json::json!({
+        "goog:chromeOptions": { "binary": "/usr/bin/chromium" },
+    })))
+    .accept_insecure_certs(true);
+    let caps = b.capabilities_json();
+    assert_eq!(
+        caps,
+        serde_json::json!({
+            "browserName": "chrome",
+            "acceptInsecureCerts": true,
+            "pageLoadStrategy": "normal",
+            "goog:chromeOptions": {
+                "args": ["--headless"],
+                "binary": "/usr/bin/chromium",
+                "w3c": true,
+            },
+        })
+    );
+
+    // it is exactly what is sent to the driver
+    let mut c = b
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    c.close().await.unwrap();
+    let received = driver.received.lock().unwrap();
+    assert_eq!(received[0].body["capabilities"]["alwaysMatch"], caps);
+}
+
+#[tokio::test]
+async fn failed_actions_are_released() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let keys = KeySequence::new().chord(&[Key::Shift, Key::Char('a')]);
+    c.send_key_sequence(keys)
+        .await
+        .expect_err("mock driver should fail the actions");
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    let requests: Vec<_> = received
+        .iter()
+        .map(|req| (req.method.clone(), req.path.as_str()))
+        .collect();
+    assert_eq!(
+        requests[1..3],
+        [
+            (Method::POST, "/session/mock/actions"),
+            (Method::DELETE, "/session/mock/actions"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn cookies() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let cookie = fantoccini::cookie::Cookie::build("flavor", "oatmeal")
+        .path("/")
+        .http_only(true)
+        .same_site(fantoccini::cookie::SameSite::Lax)
+        .finish();
+    c.add_cookie(cookie).await.unwrap();
+    match c.get_named_cookie("first flavor").await {
+        Err(fantoccini::error::CmdError::NoSuchCookie(_)) => {}
+        v => panic!("expected NoSuchCookie, got {:?}", v),
+    }
+    c.delete_cookie("first flavor").await.unwrap();
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    let requests: Vec<_> = received
+        .iter()
+        .map(|req| (req.method.clone(), req.path.as_str()))
+        .collect();
+    assert_eq!(
+        requests[1..4],
+        [
+            (Method::POST, "/session/mock/cookie"),
+            (Method::GET, "/session/mock/cookie/first%20flavor"),
+            (Method::DELETE, "/session/mock/cookie/first%20flavor"),
+        ]
+    );
+    let cookie = &received[1].body["cookie"];
+    assert_eq!(cookie["name"], "flavor");
+    assert_eq!(cookie["value"], "oatmeal");
+    assert_eq!(cookie["path"], "/");
+    assert_eq!(cookie["httpOnly"], true);
+    assert_eq!(cookie["secure"], false);
+    assert_eq!(cookie["sameSite"], "Lax");
+}
+
+#[tokio::test]
+async fn set_timeouts() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let timeouts = Timeouts {
+        implicit: Some(Duration::from_millis(1500)),
+        ..Default::default()
+    };
+    c.set_timeouts(timeouts).await.unwrap();
+    c.close().await.unwrap();
+
+    // drivers differ on whether they accept nulls, so unset timeouts are left out altogether
+    let received = driver.received.lock().unwrap();
+    assert_eq!(received[1].path, "/session/mock/timeouts");
+    assert_eq!(received[1].body, serde_json::json!({ "implicit": 1500 }));
+}
+
+#[tokio::test]
+async fn mock_geolocation_firefox() {
+    let mut b = builder();
+    b.merge_capabilities(json_caps(serde_json::json!({
+        "moz:firefoxOptions": { "args": ["--headless"] },
+    })))
+    .mock_geolocation_firefox(59.91, 10.75);
+    let caps = b.capabilities_json();
+
+    let options = &caps["moz:firefoxOptions"];
+    assert_eq!(options["args"], serde_json::json!(["--headless"]));
+    let url = options["prefs"]["geo.provider.network.url"]
+        .as_str()
+        .unwrap();
+    let location: Json = serde_json::from_str(url.strip_prefix("data:application/json,").unwrap())
+        .expect("the location should be JSON");
+    assert_eq!(
+        location["location"],
+        serde_json::json!({ "lat": 59.91, "lng": 10.75 })
+    );
+}
+
+#[tokio::test]
+async fn user_data_dir() {
+    let dir = std::path::Path::new("/tmp/profile");
+
+    let mut b = builder();
+    b.merge_capabilities(json_caps(serde_json::json!({
+        "goog:chromeOptions": { "args": ["--headless"] },
+    })))
+    .user_data_dir(dir);
+    let caps = b.capabilities_json();
+    assert_eq!(
+        caps["goog:chromeOptions"]["args"],
+        serde_json::json!(["--headless", "--user-data-dir=/tmp/profile"])
+    );
+    assert_eq!(
+        caps["moz:firefoxOptions"]["args"],
+        serde_json::json!(["-profile", "/tmp/profile"])
+    );
+
+    let mut b = builder();
+    b.merge_capabilities(json_caps(serde_json::json!({ "browserName": "firefox" })))
+        .user_data_dir(dir);
+    let caps = b.capabilities_json();
+    assert!(caps["goog:chromeOptions"].get("args").is_none());
+    assert_eq!(
+        caps["moz:firefoxOptions"]["args"],
+        serde_json::json!(["-profile", "/tmp/profile"])
+    );
+}
+
+#[tokio::test]
+async fn protocol() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    assert_eq!(c.protocol(), Protocol::W3C);
+    assert!(!c.is_legacy());
+    c.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn browser() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let browser = c.browser().await.unwrap();
+    assert_eq!(browser, Browser::Edge);
+    assert!(browser.is_chromium());
+    let version = c.browser_version().await.unwrap().unwrap();
+    assert_eq!(version.major(), 120);
+    assert_eq!(version.parts(), [120, 0, 2210, 61]);
+    assert_eq!(version.to_string(), "120.0.2210.61");
+    c.close().await.unwrap();
+
+    // the capabilities come with the session, so no commands are sent for them
+    let received = driver.received.lock().unwrap();
+    assert_eq!(received.len(), 2);
+}
+
+#[tokio::test]
+async fn restart() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut caps = serde_json::Map::new();
+    caps.insert("browserName".to_string(), serde_json::json!("mock"));
+    let mut c = builder()
+        .capabilities(caps)
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let old = c.session_id().await.unwrap();
+    c.restart().await.unwrap();
+    let new = c.session_id().await.unwrap();
+    assert_eq!(old.as_deref(), Some("mock"));
+    assert_eq!(new.as_deref(), Some("mock1"));
+    assert_eq!(c.current_url().await.unwrap().as_str(), "about:blank");
+    c.close().await.unwrap();
+
+    let received = driver.received.lock().unwrap();
+    let requests: Vec<_> = received
+        .iter()
+        .map(|req| (req.method.clone(), req.path.as_str()))
+        .collect();
+    assert_eq!(
+        requests,
+        vec![
+            (Method::POST, "/session"),
+            (Method::DELETE, "/session/mock"),
+            (Method::POST, "/session"),
+            (Method::GET, "/session/mock1/url"),
+            (Method::DELETE, "/session/mock1"),
+        ]
+    );
+    // the new session asks for the same capabilities as the old one
+    assert_eq!(received[0].body, received[2].body);
+}
+
+#[tokio::test]
+async fn debug_state() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let state = c.debug_state().await;
+    c.close().await.unwrap();
+
+    // the mock driver only knows the URL, so everything else is reported as an error
+    assert_eq!(state.url.as_deref(), Some("about:blank"));
+    assert_eq!(state.title, None);
+    assert_eq!(state.ready_state, None);
+    let failed: Vec<_> = state
+        .errors
+        .iter()
+        .map(|(field, _)| field.as_str())
+        .collect();
+    assert!(failed.contains(&"title"));
+    assert!(failed.contains(&"ready_state"));
+    assert!(!failed.contains(&"url"));
+
+    let json = serde_json::to_value(&state).unwrap();
+    assert_eq!(json["url"], serde_json::json!("about:blank"));
+    assert_eq!(json["title"], Json::Null);
+    assert_eq!(json["errors"][0][0], serde_json::json!(failed[0]));
+}
+
+#[cfg(feature = "reqwest")]
+#[tokio::test]
+async fn to_cookie_jar() {
+    let driver = MockDriver::default();
+    let url = driver.start();
+
+    let mut c = builder()
+        .connect(&url)
+        .await
+        .expect("failed to connect to mock driver");
+    let jar = Arc::new(c.to_cookie_jar().await.unwrap());
+    c.close().await.unwrap();
+
+    // a site that only lets in requests that carry the session cookie
+    let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0);
+    let server = Server::bind(&socket_addr).serve(make_service_fn(|_| async {
+        Ok::<_, Infallible>(service_fn(|req: Request<Body>| async move {
+            let cookie = req.headers().get(hyper::header::COOKIE);
+            let status = if cookie == Some(&"session=let-me-in".parse().unwrap()) {
+                StatusCode::OK
+            } else {
+                StatusCode::UNAUTHORIZED
+            };
+            Ok::<_, Infallible>(
+                Response::builder()
+                    .status(status)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+        }))
+    }));
+    let account = format!("http://{}/account", server.local_addr());
+    tokio::spawn(server);
+
+    let http = reqwest::Client::builder()
+        .cookie_provider(jar)
+        .build()
+        .unwrap();
+    let res = http.get(&account).send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+}
+
+#[tokio::test]
+async fn connect_with_retries() {
+    // find a free port, and only start the driver on it after a while
+    let port = std::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let driver = MockDriver::default();
+    let started = driver.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        started.start_on(port);
+    });
+
+    let url = format!("http://{}:{}", Ipv4Addr::LOCALHOST, port);
+    builder()
+        .connect(&url)
+        .await
+        .expect_err("the driver should not be up yet");
+    let mut c = builder()
+        .connect_with_retries(&url, 10, Duration::from_millis(50))
+        .await
+        .expect("failed to connect to mock driver");
+    c.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn connect_with_retries_rejected() {
+    let driver = MockDriver::requiring_auth();
+    let url = driver.start();
+
+    // the driver is up, but refuses the session, which is not worth retrying
+    builder()
+        .connect_with_retries(&url, 3, Duration::from_millis(50))
+        .await
+        .expect_err("mock driver should reject unauthenticated sessions");
+    assert_eq!(driver.received.lock().unwrap().len(), 1);
+}
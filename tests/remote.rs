@@ -109,7 +109,7 @@ async fn raw_inner(mut c: Client) -> Result<(), error::CmdError> {
         .await?;
 
     // and voilla, we now have the bytes for the Wikipedia logo!
-    assert!(pixels.len() > 0);
+    assert!(!pixels.is_empty());
     println!("Wikipedia logo is {}b", pixels.len());
 
     c.close().await
@@ -0,0 +1,3 @@
+window.addEventListener('DOMContentLoaded', function () {
+    document.getElementById('loaded').textContent = 'script loaded';
+});